@@ -0,0 +1,95 @@
+// Draws the seating layout in the terminal as it changes, and optionally
+// writes every frame to a text log.
+
+use crate::{Engine, Seat};
+use grid::Grid;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::thread;
+use std::time::Duration;
+
+const FLOOR_COLOR: &str = "\x1b[90m";
+const EMPTY_COLOR: &str = "\x1b[32m";
+const OCCUPIED_COLOR: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+// Renders a layout in the puzzle's format, with ANSI colours for floor,
+// empty and occupied seats if `color` is set.
+pub fn render(map: &Grid<Seat>, color: bool) -> String {
+    let mut output = String::new();
+    for row in map.rows() {
+        let mut current_color = "";
+        for seat in row {
+            let (c, seat_color) = match seat {
+                Seat::Floor => ('.', FLOOR_COLOR),
+                Seat::EmptySeat => ('L', EMPTY_COLOR),
+                Seat::OccupiedSeat => ('#', OCCUPIED_COLOR),
+            };
+            if color && seat_color != current_color {
+                output.push_str(seat_color);
+                current_color = seat_color;
+            }
+            output.push(c);
+        }
+        if color {
+            output.push_str(RESET);
+        }
+        output.push('\n');
+    }
+    return output;
+}
+
+pub struct Animator {
+    // Whether to draw in the terminal, or only write the log.
+    pub draw: bool,
+    pub delay: Duration,
+    pub log: Option<BufWriter<File>>,
+}
+
+impl Animator {
+    // Shows the layout after `step` rounds.
+    pub fn frame(&mut self, step: usize, engine: &Engine) -> io::Result<()> {
+        let map = engine.to_map();
+        let header = format!("step {}: {} occupied", step, engine.occupied());
+        if self.draw {
+            // Frames go to stderr so that stdout only has the answer.
+            let mut stderr = io::stderr().lock();
+            write!(stderr, "{}{}\n{}", CLEAR_SCREEN, header, render(&map, true))?;
+            stderr.flush()?;
+            thread::sleep(self.delay);
+        }
+        if let Some(log) = self.log.as_mut() {
+            write!(log, "{}\n{}\n", header, render(&map, false))?;
+        }
+        return Ok(());
+    }
+
+    pub fn finish(&mut self) -> io::Result<()> {
+        if let Some(log) = self.log.as_mut() {
+            log.flush()?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::tests::parse;
+
+    #[test]
+    fn render_without_color() {
+        let map = parse("L.#\n##.\n");
+        assert_eq!(render(&map, false), "L.#\n##.\n");
+    }
+
+    #[test]
+    fn render_with_color() {
+        let map = parse("L.#\n##.\n");
+        assert_eq!(
+            render(&map, true),
+            "\x1b[32mL\x1b[90m.\x1b[31m#\x1b[0m\n\x1b[31m##\x1b[90m.\x1b[0m\n"
+        );
+    }
+}
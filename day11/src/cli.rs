@@ -0,0 +1,161 @@
+use crate::animate::Animator;
+use crate::cycle::{self, Outcome};
+use crate::{Engine, Seat};
+use grid::{Grid, ParseError};
+use lifelike::{Neighborhood, Rule, RuleError};
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+const USAGE: &str = "<path to input text file> [--rule <rule>]
+    [--neighborhood moore|von-neumann|line-of-sight] [--max-iterations <n>]
+    [--animate] [--delay <milliseconds>] [--frames <log path>]";
+
+/// Seats passengers in the waiting area from the input file until nobody
+/// moves, using `default_rule` and `default_neighborhood` unless the command
+/// line overrides them, and prints how many seats end up occupied. Errors
+/// are printed with the usage text for the binary `name`, and the process
+/// exits with status 1, as it does when the seating never settles.
+pub fn run(name: &str, default_rule: &str, default_neighborhood: Neighborhood) {
+    if let Err(err) = run_args(default_rule, default_neighborhood) {
+        eprintln!("{}", err);
+        eprintln!("usage: {} {}", name, USAGE);
+        process::exit(1);
+    }
+}
+
+fn run_args(default_rule: &str, default_neighborhood: Neighborhood) -> Result<(), InputError> {
+    let mut args = Vec::new();
+    let mut rule: Rule = default_rule.parse().map_err(InputError::InvalidRule)?;
+    let mut neighborhood = default_neighborhood;
+    let mut max_iterations = 1_000_000usize;
+    let mut animate = false;
+    let mut delay_ms = 200u64;
+    let mut frames_path: Option<String> = None;
+    let mut arg_iter = env::args();
+    while let Some(arg) = arg_iter.next() {
+        let mut value = || {
+            arg_iter
+                .next()
+                .ok_or_else(|| InputError::InvalidArgument(format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "--rule" => rule = value()?.parse().map_err(InputError::InvalidRule)?,
+            "--neighborhood" => neighborhood = value()?.parse().map_err(InputError::InvalidRule)?,
+            "--max-iterations" => max_iterations = parse_number("--max-iterations", &value()?)?,
+            "--animate" => animate = true,
+            "--delay" => delay_ms = parse_number("--delay", &value()?)?,
+            "--frames" => frames_path = Some(value()?),
+            _ => args.push(arg),
+        }
+    }
+    let filename = args
+        .get(1)
+        .ok_or_else(|| InputError::InvalidArgument("missing input file".to_string()))?;
+    rule.check_counts(neighborhood, 2)
+        .map_err(InputError::InvalidRule)?;
+    let file = File::open(filename)
+        .map_err(|err| InputError::Io(format!("couldn't open {}: {}", filename, err)))?;
+    // Short rows are padded with floor, as if the room were rectangular.
+    let map =
+        Grid::parse_padded(io::BufReader::new(file), Seat::Floor, parse_cell).map_err(|err| {
+            match err {
+                ParseError::InvalidCell(err) => err,
+                err => InputError::InvalidMap(err.to_string()),
+            }
+        })?;
+    let mut engine = Engine::new(&map, &rule, neighborhood);
+    let log = match frames_path {
+        Some(path) => {
+            let file = File::create(&path)
+                .map_err(|err| InputError::Io(format!("couldn't create {}: {}", path, err)))?;
+            Some(BufWriter::new(file))
+        }
+        None => None,
+    };
+    let mut animator = match (animate, log) {
+        (false, None) => None,
+        (draw, log) => Some(Animator {
+            draw,
+            delay: Duration::from_millis(delay_ms),
+            log,
+        }),
+    };
+    // Frames stop being drawn at the first one that can't be written, but
+    // the seating still runs to the end before the error is reported.
+    let mut frame_error = None;
+    let outcome = cycle::run(&mut engine, max_iterations, |step, engine| {
+        if let (Some(animator), None) = (animator.as_mut(), &frame_error) {
+            frame_error = animator.frame(step, engine).err();
+        }
+    });
+    if let Some(animator) = animator.as_mut() {
+        let written = match frame_error {
+            Some(err) => Err(err),
+            None => animator.finish(),
+        };
+        written.map_err(|err| InputError::Io(format!("couldn't write a frame: {}", err)))?;
+    }
+    match outcome {
+        Outcome::Stable { steps } => {
+            eprintln!("stable after {} steps", steps);
+            println!("{}", engine.occupied());
+        }
+        Outcome::Cycle {
+            start,
+            period,
+            occupied,
+        } => {
+            let counts: Vec<String> = occupied.iter().map(|n| n.to_string()).collect();
+            eprintln!(
+                "enters a period-{} cycle at step {}; occupied seats across the cycle: {}",
+                period,
+                start,
+                counts.join(", ")
+            );
+            process::exit(1);
+        }
+        Outcome::Unresolved { steps } => {
+            eprintln!("neither stable nor cycling after {} steps", steps);
+            process::exit(1);
+        }
+    }
+    return Ok(());
+}
+
+fn parse_number<T: FromStr>(name: &str, s: &str) -> Result<T, InputError> {
+    return s
+        .parse()
+        .map_err(|_| InputError::InvalidArgument(format!("{} needs a number, not {}", name, s)));
+}
+
+fn parse_cell(c: char) -> Result<Seat, InputError> {
+    return Seat::from_char(c).ok_or(InputError::InvalidCharacter(c));
+}
+
+#[derive(Debug)]
+enum InputError {
+    InvalidArgument(String),
+    InvalidCharacter(char),
+    InvalidMap(String),
+    InvalidRule(RuleError),
+    Io(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::InvalidArgument(s) => write!(f, "{}", s),
+            InputError::InvalidCharacter(c) => write!(f, "invalid character: {}", c),
+            InputError::InvalidMap(s) => write!(f, "invalid map: {}", s),
+            InputError::InvalidRule(e) => write!(f, "{}", e),
+            InputError::Io(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
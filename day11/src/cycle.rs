@@ -0,0 +1,149 @@
+// Detects whether a seating simulation settles, and if it doesn't, the
+// cycle it falls into.
+
+use crate::Engine;
+use std::collections::HashMap;
+
+pub enum Outcome {
+    // No seat changes after this many rounds.
+    Stable {
+        steps: usize,
+    },
+    // The layout after `start` rounds repeats every `period` rounds from
+    // then on. `occupied[i]` is the number of occupied seats after
+    // `start + i` rounds.
+    Cycle {
+        start: usize,
+        period: usize,
+        occupied: Vec<usize>,
+    },
+    // Neither happened within this many rounds.
+    Unresolved {
+        steps: usize,
+    },
+}
+
+// Runs `engine` until its layout stops changing or repeats an earlier
+// layout, for at most `max_iterations` rounds. Layouts are looked up by
+// their hashes, and a layout with the same hash as an earlier one is
+// compared with it seat by seat before a cycle is reported. `on_step` is
+// called with the number of rounds run so far, first with the starting
+// layout and then after each round that changes a seat.
+pub fn run<F: FnMut(usize, &Engine)>(
+    engine: &mut Engine,
+    max_iterations: usize,
+    mut on_step: F,
+) -> Outcome {
+    on_step(0, engine);
+    let initial = engine.seats().to_vec();
+    // The rounds after which each hash was seen, in order.
+    let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut occupied = vec![engine.occupied()];
+    seen.insert(engine.state_hash(), vec![0]);
+    for steps in 0..max_iterations {
+        if engine.step() == 0 {
+            return Outcome::Stable { steps };
+        }
+        let round = steps + 1;
+        on_step(round, engine);
+        occupied.push(engine.occupied());
+        let hash = engine.state_hash();
+        if let Some(rounds) = seen.get(&hash) {
+            if let Some(start) = find_repeat(engine, &initial, rounds) {
+                return Outcome::Cycle {
+                    start,
+                    period: round - start,
+                    occupied: occupied[start..round].to_vec(),
+                };
+            }
+        }
+        seen.entry(hash).or_default().push(round);
+    }
+    return Outcome::Unresolved {
+        steps: max_iterations,
+    };
+}
+
+// Returns the first of `rounds` after which the layout was the same as
+// `engine`'s current one, by replaying the rounds from the `initial` seats.
+// This only happens on a hash match, so it's rare enough not to matter.
+fn find_repeat(engine: &Engine, initial: &[bool], rounds: &[usize]) -> Option<usize> {
+    let mut replay = engine.restarted(initial);
+    let mut replayed = 0;
+    for &round in rounds {
+        while replayed < round {
+            replay.step();
+            replayed += 1;
+        }
+        if replay.seats() == engine.seats() {
+            return Some(round);
+        }
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::tests::{parse, EXAMPLE};
+    use lifelike::Neighborhood;
+
+    const BLINKER: &str = "LLLLL
+LLLLL
+L###L
+LLLLL
+LLLLL
+";
+
+    fn engine(map: &str, rule: &str) -> Engine {
+        return Engine::new(&parse(map), &rule.parse().unwrap(), Neighborhood::Moore);
+    }
+
+    #[test]
+    fn stable() {
+        let mut engine = engine(EXAMPLE, "B0/S0123");
+        let mut rounds = Vec::new();
+        let outcome = run(&mut engine, 100, |round, _| rounds.push(round));
+        assert!(matches!(outcome, Outcome::Stable { steps: 5 }));
+        assert_eq!(rounds, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(engine.occupied(), 37);
+    }
+
+    #[test]
+    fn blinker_cycles() {
+        let mut engine = engine(BLINKER, "B3/S23");
+        match run(&mut engine, 100, |_, _| {}) {
+            Outcome::Cycle {
+                start,
+                period,
+                occupied,
+            } => {
+                assert_eq!((start, period), (0, 2));
+                assert_eq!(occupied, vec![3, 3]);
+            }
+            _ => panic!("expected a cycle"),
+        }
+    }
+
+    #[test]
+    fn unresolved() {
+        let mut engine = engine(BLINKER, "B3/S23");
+        assert!(matches!(
+            run(&mut engine, 1, |_, _| {}),
+            Outcome::Unresolved { steps: 1 }
+        ));
+    }
+
+    #[test]
+    fn find_repeat_compares_layouts() {
+        let mut engine = engine(BLINKER, "B3/S23");
+        let initial = engine.seats().to_vec();
+        engine.step();
+        engine.step();
+        // After two rounds the blinker is back where it started, and not
+        // where it was after one round, whatever the hashes say.
+        assert_eq!(find_repeat(&engine, &initial, &[1]), None);
+        assert_eq!(find_repeat(&engine, &initial, &[0]), Some(0));
+        assert_eq!(find_repeat(&engine, &initial, &[1, 2]), Some(2));
+    }
+}
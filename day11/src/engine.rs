@@ -0,0 +1,394 @@
+use crate::Seat;
+use grid::{Edges, Grid};
+use lifelike::{Neighborhood, Rule};
+
+const NONE: u32 = u32::MAX;
+
+/// A seating simulation for large layouts.
+///
+/// Seats are numbered in row-major order, skipping floor. The seats each
+/// seat can see are found once up front and stored as an adjacency list, and
+/// occupancy is kept in two flat buffers that swap roles every round. Only
+/// seats that changed in the previous round, and the seats that can see
+/// them, are evaluated, so regions that have settled cost nothing.
+#[derive(Clone)]
+pub struct Engine {
+    width: usize,
+    height: usize,
+    // The cell (y * width + x) of each seat.
+    seat_cells: Vec<u32>,
+    // The neighbors of seat i are neighbors[offsets[i]..offsets[i + 1]].
+    offsets: Vec<u32>,
+    neighbors: Vec<u32>,
+    current: Vec<bool>,
+    next: Vec<bool>,
+    // Seats that changed in the last round, which are stale in `next`.
+    changed: Vec<u32>,
+    // Seats to evaluate in the next round, and whether each seat is queued.
+    // When most seats changed, every seat is evaluated instead.
+    active: Vec<u32>,
+    queued: Vec<bool>,
+    all_active: bool,
+    occupied: usize,
+    // XOR of `seat_key` over the occupied seats.
+    hash: u64,
+    // Whether a seat is occupied next round, indexed by its occupied
+    // neighbor count, for empty and occupied seats.
+    birth: Vec<bool>,
+    survival: Vec<bool>,
+}
+
+impl Engine {
+    pub fn new(map: &Grid<Seat>, rule: &Rule, neighborhood: Neighborhood) -> Engine {
+        let (width, height) = (map.width(), map.height());
+
+        let mut seat_ids = vec![NONE; width * height];
+        let mut seat_cells = Vec::new();
+        let mut current = Vec::new();
+        for (cell, &seat) in map.cells().iter().enumerate() {
+            if seat != Seat::Floor {
+                seat_ids[cell] = seat_cells.len() as u32;
+                seat_cells.push(cell as u32);
+                current.push(seat == Seat::OccupiedSeat);
+            }
+        }
+        let num_seats = seat_cells.len();
+
+        let directions = crate::directions(neighborhood);
+        let line_of_sight = neighborhood == Neighborhood::LineOfSight;
+        // Start with a fixed number of slots per seat, one per direction,
+        // and compact them into the adjacency list afterwards.
+        let mut slots = vec![NONE; num_seats * directions.len()];
+        let mut visible = vec![NONE; width * height];
+        for (d, &(dx, dy)) in directions.iter().enumerate() {
+            // visible[cell] is the seat seen from `cell` in this direction.
+            // Cells are visited so that the next cell in the direction is
+            // always visited first, which lets floor pass on what it sees.
+            for i in 0..height {
+                let y = if dy > 0 { height - 1 - i } else { i };
+                for j in 0..width {
+                    let x = if dx > 0 { width - 1 - j } else { j };
+                    visible[map.index_of(x, y)] = match map.offset((x, y), (dx, dy), Edges::Bounded)
+                    {
+                        None => NONE,
+                        Some((nx, ny)) => {
+                            let next_cell = map.index_of(nx, ny);
+                            match seat_ids[next_cell] {
+                                NONE if line_of_sight => visible[next_cell],
+                                id => id,
+                            }
+                        }
+                    };
+                }
+            }
+            for (seat, &cell) in seat_cells.iter().enumerate() {
+                slots[seat * directions.len() + d] = visible[cell as usize];
+            }
+        }
+        drop(visible);
+        drop(seat_ids);
+        let mut offsets = Vec::with_capacity(num_seats + 1);
+        let mut len = 0;
+        for seat in 0..num_seats {
+            offsets.push(len as u32);
+            for d in 0..directions.len() {
+                let neighbor = slots[seat * directions.len() + d];
+                if neighbor != NONE {
+                    slots[len] = neighbor;
+                    len += 1;
+                }
+            }
+        }
+        offsets.push(len as u32);
+        slots.truncate(len);
+        slots.shrink_to_fit();
+
+        let (occupied, hash) = summarize(&current);
+        return Engine {
+            width,
+            height,
+            seat_cells,
+            offsets,
+            neighbors: slots,
+            next: current.clone(),
+            current,
+            changed: Vec::new(),
+            active: Vec::new(),
+            queued: vec![false; num_seats],
+            all_active: true,
+            occupied,
+            hash,
+            birth: (0..=directions.len()).map(|n| rule.is_birth(n)).collect(),
+            survival: (0..=directions.len())
+                .map(|n| rule.is_survival(n))
+                .collect(),
+        };
+    }
+
+    /// Runs one round of seating and returns the number of seats that
+    /// changed.
+    pub fn step(&mut self) -> usize {
+        for &seat in &self.changed {
+            self.next[seat as usize] = self.current[seat as usize];
+        }
+        self.changed.clear();
+        if self.all_active {
+            for seat in 0..self.current.len() {
+                self.evaluate(seat);
+            }
+        } else if self.active.len() * 8 > self.current.len() {
+            // With this many seats to evaluate, visiting them in order keeps
+            // reads of the adjacency list sequential, which is faster than
+            // following the queue.
+            for seat in 0..self.current.len() {
+                if self.queued[seat] {
+                    self.evaluate(seat);
+                }
+            }
+        } else {
+            for i in 0..self.active.len() {
+                self.evaluate(self.active[i] as usize);
+            }
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+
+        self.active.clear();
+        self.all_active = self.changed.len() * 8 > self.current.len();
+        for &seat in &self.changed {
+            let seat = seat as usize;
+            if self.current[seat] {
+                self.occupied += 1;
+            } else {
+                self.occupied -= 1;
+            }
+            self.hash ^= seat_key(seat);
+            if self.all_active {
+                continue;
+            }
+            let start = self.offsets[seat] as usize;
+            let end = self.offsets[seat + 1] as usize;
+            for &queued_seat in std::iter::once(&(seat as u32)).chain(&self.neighbors[start..end]) {
+                if !self.queued[queued_seat as usize] {
+                    self.queued[queued_seat as usize] = true;
+                    self.active.push(queued_seat);
+                }
+            }
+        }
+        return self.changed.len();
+    }
+
+    // Works out whether `seat` is occupied next round.
+    fn evaluate(&mut self, seat: usize) {
+        self.queued[seat] = false;
+        let neighbors =
+            &self.neighbors[self.offsets[seat] as usize..self.offsets[seat + 1] as usize];
+        let count = neighbors
+            .iter()
+            .filter(|&&neighbor| self.current[neighbor as usize])
+            .count();
+        let occupied = self.current[seat];
+        let next = match occupied {
+            true => self.survival[count],
+            false => self.birth[count],
+        };
+        if next != occupied {
+            self.next[seat] = next;
+            self.changed.push(seat as u32);
+        }
+    }
+
+    /// The number of occupied seats.
+    pub fn occupied(&self) -> usize {
+        return self.occupied;
+    }
+
+    /// A hash of which seats are occupied. It's updated as seats change, so
+    /// it costs nothing to check every round.
+    pub fn state_hash(&self) -> u64 {
+        return self.hash;
+    }
+
+    /// Whether each seat is occupied, in row-major order of the seats.
+    pub fn seats(&self) -> &[bool] {
+        return &self.current;
+    }
+
+    /// Returns a copy of this engine with the seats set to `seats`, as
+    /// returned by `seats`.
+    pub fn restarted(&self, seats: &[bool]) -> Engine {
+        assert_eq!(seats.len(), self.current.len(), "wrong number of seats");
+        let (occupied, hash) = summarize(seats);
+        return Engine {
+            current: seats.to_vec(),
+            next: seats.to_vec(),
+            changed: Vec::new(),
+            active: Vec::new(),
+            queued: vec![false; seats.len()],
+            all_active: true,
+            occupied,
+            hash,
+            ..self.clone()
+        };
+    }
+
+    /// Returns the current layout.
+    pub fn to_map(&self) -> Grid<Seat> {
+        let mut cells = vec![Seat::Floor; self.width * self.height];
+        for (seat, &cell) in self.seat_cells.iter().enumerate() {
+            cells[cell as usize] = match self.current[seat] {
+                true => Seat::OccupiedSeat,
+                false => Seat::EmptySeat,
+            };
+        }
+        return Grid::from_cells(self.width, self.height, cells);
+    }
+}
+
+// Counts the occupied seats and hashes the layout.
+fn summarize(seats: &[bool]) -> (usize, u64) {
+    let occupied = seats.iter().filter(|&&occupied| occupied).count();
+    let hash = seats
+        .iter()
+        .enumerate()
+        .filter(|&(_, &occupied)| occupied)
+        .fold(0, |hash, (seat, _)| hash ^ seat_key(seat));
+    return (occupied, hash);
+}
+
+// A pseudorandom key for each seat (splitmix64), so that XORing the keys of
+// the occupied seats gives a well-distributed hash of the layout.
+fn seat_key(seat: usize) -> u64 {
+    let mut z = (seat as u64)
+        .wrapping_add(1)
+        .wrapping_mul(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    return z ^ (z >> 31);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::directions;
+
+    pub(crate) const EXAMPLE: &str = "L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    pub(crate) fn parse(map: &str) -> Grid<Seat> {
+        return Grid::parse(map.as_bytes(), |c| Seat::from_char(c).ok_or(c)).unwrap();
+    }
+
+    // Runs one round by looking around every seat in the layout, as a
+    // reference for the engine.
+    fn simulate(map: &Grid<Seat>, rule: &Rule, neighborhood: Neighborhood) -> Grid<Seat> {
+        let line_of_sight = neighborhood == Neighborhood::LineOfSight;
+        return Grid::from_cells(
+            map.width(),
+            map.height(),
+            map.iter()
+                .map(|(position, &seat)| {
+                    if seat == Seat::Floor {
+                        return seat;
+                    }
+                    let count = directions(neighborhood)
+                        .iter()
+                        .filter(|&&direction| {
+                            let limit = if line_of_sight { usize::MAX } else { 1 };
+                            map.ray(position, direction, Edges::Bounded)
+                                .take(limit)
+                                .map(|position| map[position])
+                                .find(|&seat| seat != Seat::Floor)
+                                == Some(Seat::OccupiedSeat)
+                        })
+                        .count();
+                    match rule.next_state(seat == Seat::OccupiedSeat, count) {
+                        true => Seat::OccupiedSeat,
+                        false => Seat::EmptySeat,
+                    }
+                })
+                .collect(),
+        );
+    }
+
+    fn settle(rule: &str, neighborhood: Neighborhood) -> usize {
+        let mut engine = Engine::new(&parse(EXAMPLE), &rule.parse().unwrap(), neighborhood);
+        while engine.step() > 0 {}
+        return engine.occupied();
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(settle("B0/S0123", Neighborhood::Moore), 37);
+        assert_eq!(settle("B0/S01234", Neighborhood::LineOfSight), 26);
+    }
+
+    #[test]
+    fn matches_simulate() {
+        // A layout with a few seats occupied, so that rules without B0 have
+        // something to work with.
+        let mixed = EXAMPLE.replacen("LLL", "L##", 3).replacen("LL", "#L", 4);
+        let cases = [
+            ("B0/S0123", Neighborhood::Moore),
+            ("B0/S01234", Neighborhood::LineOfSight),
+            ("B3/S23", Neighborhood::Moore),
+            ("B1/S1", Neighborhood::VonNeumann),
+            ("B2/S0", Neighborhood::LineOfSight),
+        ];
+        for &(rule, neighborhood) in cases.iter() {
+            let rule: Rule = rule.parse().unwrap();
+            for layout in &[EXAMPLE, mixed.as_str()] {
+                let mut map = parse(layout);
+                let mut engine = Engine::new(&map, &rule, neighborhood);
+                for round in 1..=20 {
+                    let next = simulate(&map, &rule, neighborhood);
+                    let changed = next
+                        .cells()
+                        .iter()
+                        .zip(map.cells())
+                        .filter(|(a, b)| a != b)
+                        .count();
+                    assert_eq!(engine.step(), changed, "{} round {}", rule, round);
+                    assert_eq!(engine.to_map(), next, "{} round {}", rule, round);
+                    let occupied = next
+                        .cells()
+                        .iter()
+                        .filter(|&&seat| seat == Seat::OccupiedSeat)
+                        .count();
+                    assert_eq!(engine.occupied(), occupied);
+                    assert_eq!(engine.state_hash(), summarize(engine.seats()).1);
+                    map = next;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn restarted() {
+        let rule: Rule = "B0/S0123".parse().unwrap();
+        let map = parse(EXAMPLE);
+        let mut engine = Engine::new(&map, &rule, Neighborhood::Moore);
+        let initial = engine.seats().to_vec();
+        engine.step();
+        engine.step();
+        let after_two = engine.to_map();
+        let mut restarted = engine.restarted(&initial);
+        assert_eq!(restarted.to_map(), map);
+        assert_eq!(
+            restarted.state_hash(),
+            Engine::new(&map, &rule, Neighborhood::Moore).state_hash()
+        );
+        restarted.step();
+        restarted.step();
+        assert_eq!(restarted.to_map(), after_two);
+        assert_eq!(restarted.occupied(), engine.occupied());
+    }
+}
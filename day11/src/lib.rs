@@ -0,0 +1,50 @@
+#![allow(clippy::needless_return)]
+
+use grid::{ADJACENT, ORTHOGONAL};
+use lifelike::Neighborhood;
+use std::fmt;
+
+pub mod animate;
+pub mod cli;
+pub mod cycle;
+pub mod engine;
+
+pub use engine::Engine;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Seat {
+    Floor,
+    EmptySeat,
+    OccupiedSeat,
+}
+
+impl Seat {
+    pub fn from_char(c: char) -> Option<Seat> {
+        return match c {
+            '.' => Some(Seat::Floor),
+            'L' => Some(Seat::EmptySeat),
+            '#' => Some(Seat::OccupiedSeat),
+            _ => None,
+        };
+    }
+}
+
+impl fmt::Display for Seat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            Seat::Floor => '.',
+            Seat::EmptySeat => 'L',
+            Seat::OccupiedSeat => '#',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+/// The directions in which a seat looks for neighbors.
+pub fn directions(neighborhood: Neighborhood) -> &'static [(isize, isize)] {
+    return match neighborhood {
+        Neighborhood::VonNeumann => &ORTHOGONAL,
+        Neighborhood::Moore | Neighborhood::LineOfSight => &ADJACENT,
+    };
+}
@@ -0,0 +1,141 @@
+use crate::models::{HeadingShip, WaypointShip};
+use crate::vector::{Exact, Free};
+use crate::{navigate, parse_instructions, trace, Instruction, MovementModel};
+use std::env;
+use std::fs::File;
+use std::io;
+use std::process;
+use std::str::FromStr;
+
+const USAGE: &str = "usage: <path to input text file> [--model heading|waypoint]
+    [--any-angle [tolerance]] [--csv <trace path>] [--svg <trace path>]";
+
+// Coordinates within this distance of an integer are snapped to it after
+// every instruction in --any-angle mode.
+const DEFAULT_TOLERANCE: f64 = 1e-9;
+
+#[derive(Clone, Copy)]
+pub enum Model {
+    Heading,
+    Waypoint,
+}
+
+impl FromStr for Model {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Model, String> {
+        return match s {
+            "heading" => Ok(Model::Heading),
+            "waypoint" => Ok(Model::Waypoint),
+            _ => Err(format!("unknown movement model: {}", s)),
+        };
+    }
+}
+
+/// Steers the ship through the instructions in the input file with
+/// `default_model`, or the model chosen with --model, and prints its
+/// Manhattan distance from the start. Errors are printed with the usage
+/// text, and the process exits with status 1.
+pub fn run(default_model: Model) {
+    if let Err(err) = run_args(default_model) {
+        eprintln!("{}", err);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run_args(default_model: Model) -> Result<(), String> {
+    let mut args = Vec::new();
+    let mut model = default_model;
+    let mut tolerance: Option<f64> = None;
+    let mut csv_path: Option<String> = None;
+    let mut svg_path: Option<String> = None;
+    let mut arg_iter = env::args().peekable();
+    while let Some(arg) = arg_iter.next() {
+        let mut value = || {
+            arg_iter
+                .next()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--model" => model = value()?.parse()?,
+            "--any-angle" => {
+                tolerance = Some(match arg_iter.peek().map(|s| s.parse::<f64>()) {
+                    Some(Ok(tolerance)) => {
+                        arg_iter.next();
+                        tolerance
+                    }
+                    _ => DEFAULT_TOLERANCE,
+                })
+            }
+            "--csv" => csv_path = Some(value()?),
+            "--svg" => svg_path = Some(value()?),
+            _ => args.push(arg),
+        }
+    }
+    let filename = args.get(1).ok_or("missing input file")?;
+    let file =
+        File::open(filename).map_err(|err| format!("couldn't open {}: {}", filename, err))?;
+    let instructions =
+        parse_instructions(io::BufReader::new(file)).map_err(|err| err.to_string())?;
+
+    let csv_path = csv_path.as_deref();
+    let svg_path = svg_path.as_deref();
+    return match (model, tolerance) {
+        (Model::Heading, None) => run_model(
+            HeadingShip {
+                ship: Exact { east: 0, north: 0 },
+                heading: Exact { east: 1, north: 0 },
+            },
+            &instructions,
+            csv_path,
+            svg_path,
+        ),
+        (Model::Heading, Some(tolerance)) => run_model(
+            HeadingShip {
+                ship: Free::new(0.0, 0.0, tolerance),
+                heading: Free::new(1.0, 0.0, tolerance),
+            },
+            &instructions,
+            csv_path,
+            svg_path,
+        ),
+        (Model::Waypoint, None) => run_model(
+            WaypointShip {
+                ship: Exact { east: 0, north: 0 },
+                waypoint: Exact { east: 10, north: 1 },
+            },
+            &instructions,
+            csv_path,
+            svg_path,
+        ),
+        (Model::Waypoint, Some(tolerance)) => run_model(
+            WaypointShip {
+                ship: Free::new(0.0, 0.0, tolerance),
+                waypoint: Free::new(10.0, 1.0, tolerance),
+            },
+            &instructions,
+            csv_path,
+            svg_path,
+        ),
+    };
+}
+
+fn run_model<M: MovementModel>(
+    mut model: M,
+    instructions: &[Instruction],
+    csv_path: Option<&str>,
+    svg_path: Option<&str>,
+) -> Result<(), String> {
+    // The route is only recorded when it's going to be written out.
+    let mut steps = Vec::new();
+    let trace = match (csv_path, svg_path) {
+        (None, None) => None,
+        _ => Some(&mut steps),
+    };
+    navigate(&mut model, instructions, trace).map_err(|err| err.to_string())?;
+    trace::write_files(&steps, csv_path, svg_path)
+        .map_err(|err| format!("couldn't write the trace: {}", err))?;
+    println!("{}", model.manhattan_distance());
+    return Ok(());
+}
@@ -0,0 +1,188 @@
+#![allow(clippy::needless_return, clippy::enum_variant_names)]
+
+use std::fmt;
+use std::io::BufRead;
+
+pub mod cli;
+pub mod models;
+pub mod trace;
+pub mod vector;
+
+use trace::Step;
+
+#[derive(Clone, Copy)]
+pub enum Action {
+    North,
+    South,
+    East,
+    West,
+    Left,
+    Right,
+    Forward,
+}
+
+#[derive(Clone, Copy)]
+pub struct Instruction {
+    pub action: Action,
+    pub value: i64,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self.action {
+            Action::North => 'N',
+            Action::South => 'S',
+            Action::East => 'E',
+            Action::West => 'W',
+            Action::Left => 'L',
+            Action::Right => 'R',
+            Action::Forward => 'F',
+        };
+        write!(f, "{}{}", c, self.value)
+    }
+}
+
+pub fn parse_instructions<R: BufRead>(reader: R) -> Result<Vec<Instruction>, InputError> {
+    return reader
+        .lines()
+        .map(|line| line.unwrap())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_line(line.trim()))
+        .collect();
+}
+
+pub fn parse_line(line: &str) -> Result<Instruction, InputError> {
+    let mut chars = line.chars();
+    let action = match chars.next() {
+        Some('N') => Action::North,
+        Some('S') => Action::South,
+        Some('E') => Action::East,
+        Some('W') => Action::West,
+        Some('L') => Action::Left,
+        Some('R') => Action::Right,
+        Some('F') => Action::Forward,
+        Some(c) => return Err(InputError::InvalidChar(c)),
+        None => return Err(InputError::InvalidLine(line.to_string())),
+    };
+    let value = chars
+        .as_str()
+        .parse::<i64>()
+        .map_err(|_| InputError::InvalidLine(line.to_string()))?;
+    return Ok(Instruction { action, value });
+}
+
+/// What an instruction does to the ship. Implement this to add a new way of
+/// interpreting the navigation instructions.
+pub trait MovementModel {
+    type Distance: fmt::Display;
+
+    fn apply(&mut self, instruction: &Instruction) -> Result<(), InputError>;
+
+    /// The ship's position east and north of where it started.
+    fn ship(&self) -> (f64, f64);
+
+    /// The waypoint relative to the ship, for models that navigate by one.
+    fn waypoint(&self) -> Option<(f64, f64)> {
+        return None;
+    }
+
+    /// The Manhattan distance between the ship and where it started.
+    fn manhattan_distance(&self) -> Self::Distance;
+}
+
+/// Applies every instruction to `model`. With `trace`, the state before the
+/// first instruction and after each one is appended to it.
+pub fn navigate<M: MovementModel>(
+    model: &mut M,
+    instructions: &[Instruction],
+    mut trace: Option<&mut Vec<Step>>,
+) -> Result<(), InputError> {
+    let step = |label: String, model: &M| Step {
+        label,
+        ship: model.ship(),
+        waypoint: model.waypoint(),
+    };
+    if let Some(trace) = trace.as_mut() {
+        trace.push(step("start".to_string(), model));
+    }
+    for instruction in instructions {
+        model.apply(instruction)?;
+        if let Some(trace) = trace.as_mut() {
+            trace.push(step(instruction.to_string(), model));
+        }
+    }
+    return Ok(());
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    InvalidChar(char),
+    InvalidLine(String),
+    InvalidAngle(u64),
+    Overflow,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::InvalidChar(c) => write!(f, "invalid char: {}", c),
+            InputError::InvalidLine(s) => write!(f, "invalid line: {}", s),
+            InputError::InvalidAngle(degrees) => write!(
+                f,
+                "can't turn {} degrees, which isn't a multiple of 90 (use --any-angle)",
+                degrees
+            ),
+            InputError::Overflow => write!(f, "the ship went out of range"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WaypointShip;
+    use crate::vector::Exact;
+
+    fn ship() -> WaypointShip<Exact> {
+        return WaypointShip {
+            ship: Exact { east: 0, north: 0 },
+            waypoint: Exact { east: 10, north: 1 },
+        };
+    }
+
+    #[test]
+    fn navigate_with_trace() {
+        let instructions = parse_instructions("F10\nN3\nF7\nR90\nF11\n".as_bytes()).unwrap();
+        let mut steps = Vec::new();
+        let mut model = ship();
+        navigate(&mut model, &instructions, Some(&mut steps)).unwrap();
+        let labels: Vec<&str> = steps.iter().map(|step| step.label.as_str()).collect();
+        assert_eq!(labels, vec!["start", "F10", "N3", "F7", "R90", "F11"]);
+        assert_eq!(steps[0].ship, (0.0, 0.0));
+        assert_eq!(steps[5].ship, (214.0, -72.0));
+        assert_eq!(steps[5].waypoint, Some((4.0, -10.0)));
+        assert_eq!(model.manhattan_distance(), 286);
+    }
+
+    #[test]
+    fn navigate_without_trace() {
+        let instructions = parse_instructions("F10\nN3\n".as_bytes()).unwrap();
+        let mut model = ship();
+        navigate(&mut model, &instructions, None).unwrap();
+        assert_eq!(model.ship(), (100.0, 10.0));
+    }
+
+    #[test]
+    fn parse_lines() {
+        let instruction = parse_line("R270").unwrap();
+        assert_eq!(instruction.value, 270);
+        assert_eq!(instruction.to_string(), "R270");
+        assert!(matches!(
+            parse_line("Q1"),
+            Err(InputError::InvalidChar('Q'))
+        ));
+        assert!(matches!(parse_line("F"), Err(InputError::InvalidLine(_))));
+    }
+}
@@ -0,0 +1,147 @@
+// The movement models from the two parts of the puzzle.
+
+use crate::vector::Vector;
+use crate::{Action, InputError, Instruction, MovementModel};
+
+// Part 1: N/S/E/W move the ship, L/R turn it, and F moves it forward in the
+// direction it's facing.
+pub struct HeadingShip<V: Vector> {
+    pub ship: V,
+    // A unit vector in the direction the ship is facing.
+    pub heading: V,
+}
+
+impl<V: Vector> MovementModel for HeadingShip<V> {
+    type Distance = V::Distance;
+
+    fn apply(&mut self, instruction: &Instruction) -> Result<(), InputError> {
+        let value = instruction.value;
+        let negative = || value.checked_neg().ok_or(InputError::Overflow);
+        match instruction.action {
+            Action::North => self.ship = self.ship.translate(0, value)?,
+            Action::South => self.ship = self.ship.translate(0, negative()?)?,
+            Action::East => self.ship = self.ship.translate(value, 0)?,
+            Action::West => self.ship = self.ship.translate(negative()?, 0)?,
+            Action::Left => self.heading = self.heading.rotate(value)?,
+            Action::Right => self.heading = self.heading.rotate(negative()?)?,
+            Action::Forward => self.ship = self.ship.add_scaled(self.heading, value)?,
+        }
+        return Ok(());
+    }
+
+    fn ship(&self) -> (f64, f64) {
+        return self.ship.to_f64();
+    }
+
+    fn manhattan_distance(&self) -> V::Distance {
+        return self.ship.manhattan_distance();
+    }
+}
+
+// Part 2: N/S/E/W move the waypoint, L/R rotate it around the ship, and F
+// moves the ship to the waypoint the given number of times.
+pub struct WaypointShip<V: Vector> {
+    pub ship: V,
+    // The waypoint relative to the ship.
+    pub waypoint: V,
+}
+
+impl<V: Vector> MovementModel for WaypointShip<V> {
+    type Distance = V::Distance;
+
+    fn apply(&mut self, instruction: &Instruction) -> Result<(), InputError> {
+        let value = instruction.value;
+        let negative = || value.checked_neg().ok_or(InputError::Overflow);
+        match instruction.action {
+            Action::North => self.waypoint = self.waypoint.translate(0, value)?,
+            Action::South => self.waypoint = self.waypoint.translate(0, negative()?)?,
+            Action::East => self.waypoint = self.waypoint.translate(value, 0)?,
+            Action::West => self.waypoint = self.waypoint.translate(negative()?, 0)?,
+            Action::Left => self.waypoint = self.waypoint.rotate(value)?,
+            Action::Right => self.waypoint = self.waypoint.rotate(negative()?)?,
+            Action::Forward => self.ship = self.ship.add_scaled(self.waypoint, value)?,
+        }
+        return Ok(());
+    }
+
+    fn ship(&self) -> (f64, f64) {
+        return self.ship.to_f64();
+    }
+
+    fn waypoint(&self) -> Option<(f64, f64)> {
+        return Some(self.waypoint.to_f64());
+    }
+
+    fn manhattan_distance(&self) -> V::Distance {
+        return self.ship.manhattan_distance();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_instructions;
+    use crate::vector::{Exact, Free};
+
+    const EXAMPLE: &str = "F10\nN3\nF7\nR90\nF11\n";
+
+    fn distance<M: MovementModel>(mut model: M, route: &str) -> M::Distance {
+        for instruction in parse_instructions(route.as_bytes()).unwrap() {
+            model.apply(&instruction).unwrap();
+        }
+        return model.manhattan_distance();
+    }
+
+    #[test]
+    fn heading_example() {
+        let ship = HeadingShip {
+            ship: Exact { east: 0, north: 0 },
+            heading: Exact { east: 1, north: 0 },
+        };
+        assert_eq!(distance(ship, EXAMPLE), 25);
+    }
+
+    #[test]
+    fn waypoint_example() {
+        let ship = WaypointShip {
+            ship: Exact { east: 0, north: 0 },
+            waypoint: Exact { east: 10, north: 1 },
+        };
+        assert_eq!(distance(ship, EXAMPLE), 286);
+    }
+
+    #[test]
+    fn any_angle_matches_exact_on_quarter_turns() {
+        let ship = WaypointShip {
+            ship: Free::new(0.0, 0.0, 1e-9),
+            waypoint: Free::new(10.0, 1.0, 1e-9),
+        };
+        assert_eq!(distance(ship, EXAMPLE), 286.0);
+    }
+
+    #[test]
+    fn exact_rejects_other_angles() {
+        let mut ship = HeadingShip {
+            ship: Exact { east: 0, north: 0 },
+            heading: Exact { east: 1, north: 0 },
+        };
+        let instruction = crate::parse_line("L45").unwrap();
+        assert!(matches!(
+            ship.apply(&instruction),
+            Err(InputError::InvalidAngle(45))
+        ));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let mut ship = WaypointShip {
+            ship: Exact { east: 0, north: 0 },
+            waypoint: Exact { east: 10, north: 1 },
+        };
+        let instruction = crate::parse_line("F9223372036854775807").unwrap();
+        assert!(matches!(
+            ship.apply(&instruction),
+            Err(InputError::Overflow)
+        ));
+    }
+}
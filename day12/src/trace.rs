@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// The state of the ship after an instruction, in units east and north of
+/// where it started.
+pub struct Step {
+    /// The instruction as written in the input, e.g. `F10`, or `start` for
+    /// the state before any instructions.
+    pub label: String,
+    pub ship: (f64, f64),
+    /// The waypoint relative to the ship, for ships that navigate by one.
+    pub waypoint: Option<(f64, f64)>,
+}
+
+/// Writes one row per step with the ship's position and the waypoint, whose
+/// columns are empty for ships without one.
+pub fn write_csv<W: Write>(out: &mut W, steps: &[Step]) -> io::Result<()> {
+    writeln!(
+        out,
+        "step,instruction,ship_east,ship_north,waypoint_east,waypoint_north"
+    )?;
+    for (i, step) in steps.iter().enumerate() {
+        let waypoint = match step.waypoint {
+            Some((east, north)) => format!("{},{}", east, north),
+            None => ",".to_string(),
+        };
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            i, step.label, step.ship.0, step.ship.1, waypoint
+        )?;
+    }
+    return Ok(());
+}
+
+const SVG_SIZE: f64 = 800.0;
+const SVG_MARGIN: f64 = 40.0;
+
+/// Draws the ship's voyage with north up: the path between positions, a
+/// dot labelled with the instruction at each position, and an arrow from
+/// the ship to the waypoint after each step.
+pub fn write_svg<W: Write>(out: &mut W, steps: &[Step]) -> io::Result<()> {
+    let mut points: Vec<(f64, f64)> = steps.iter().map(|step| step.ship).collect();
+    points.extend(steps.iter().filter_map(|step| {
+        let (east, north) = step.waypoint?;
+        Some((step.ship.0 + east, step.ship.1 + north))
+    }));
+    let min_east = points.iter().map(|p| p.0).fold(0.0, f64::min);
+    let max_east = points.iter().map(|p| p.0).fold(0.0, f64::max);
+    let min_north = points.iter().map(|p| p.1).fold(0.0, f64::min);
+    let max_north = points.iter().map(|p| p.1).fold(0.0, f64::max);
+    let extent = (max_east - min_east).max(max_north - min_north).max(1.0);
+    let scale = (SVG_SIZE - 2.0 * SVG_MARGIN) / extent;
+    let to_svg = |(east, north): (f64, f64)| -> (f64, f64) {
+        (
+            SVG_MARGIN + (east - min_east) * scale,
+            SVG_MARGIN + (max_north - north) * scale,
+        )
+    };
+    let width = (max_east - min_east) * scale + 2.0 * SVG_MARGIN;
+    let height = (max_north - min_north) * scale + 2.0 * SVG_MARGIN;
+
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.2} {:.2}">"#,
+        width, height, width, height
+    )?;
+    writeln!(
+        out,
+        r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="orange"/></marker></defs>"#
+    )?;
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+
+    for step in steps {
+        if let Some((east, north)) = step.waypoint {
+            let (x1, y1) = to_svg(step.ship);
+            let (x2, y2) = to_svg((step.ship.0 + east, step.ship.1 + north));
+            writeln!(
+                out,
+                r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="orange" stroke-width="1" marker-end="url(#arrow)"/>"#,
+                x1, y1, x2, y2
+            )?;
+        }
+    }
+    let path: Vec<String> = steps
+        .iter()
+        .map(|step| {
+            let (x, y) = to_svg(step.ship);
+            format!("{:.2},{:.2}", x, y)
+        })
+        .collect();
+    writeln!(
+        out,
+        r#"<polyline points="{}" fill="none" stroke="steelblue" stroke-width="2"/>"#,
+        path.join(" ")
+    )?;
+    for step in steps {
+        let (x, y) = to_svg(step.ship);
+        writeln!(
+            out,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="3" fill="navy"/>"#,
+            x, y
+        )?;
+        writeln!(
+            out,
+            r#"<text x="{:.2}" y="{:.2}" font-family="monospace" font-size="10">{}</text>"#,
+            x + 5.0,
+            y - 5.0,
+            xml_escape(&step.label)
+        )?;
+    }
+    writeln!(out, "</svg>")?;
+    return Ok(());
+}
+
+fn xml_escape(s: &str) -> String {
+    return s
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+}
+
+/// Writes the CSV and SVG traces to whichever of the paths are given.
+pub fn write_files(
+    steps: &[Step],
+    csv_path: Option<&str>,
+    svg_path: Option<&str>,
+) -> io::Result<()> {
+    if let Some(path) = csv_path {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_csv(&mut out, steps)?;
+        out.flush()?;
+    }
+    if let Some(path) = svg_path {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_svg(&mut out, steps)?;
+        out.flush()?;
+    }
+    return Ok(());
+}
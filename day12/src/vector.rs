@@ -0,0 +1,133 @@
+// Positions and directions east and north of the ship's starting point,
+// with either exact integer or floating point coordinates.
+
+use crate::InputError;
+use std::fmt;
+
+pub trait Vector: Copy {
+    type Distance: fmt::Display;
+
+    fn translate(self, east: i64, north: i64) -> Result<Self, InputError>;
+
+    // Returns self + other * factor.
+    fn add_scaled(self, other: Self, factor: i64) -> Result<Self, InputError>;
+
+    // Rotates counterclockwise around the origin.
+    fn rotate(self, degrees: i64) -> Result<Self, InputError>;
+
+    fn to_f64(self) -> (f64, f64);
+
+    fn manhattan_distance(self) -> Self::Distance;
+}
+
+// Integer coordinates, which can only be rotated by multiples of 90 degrees.
+// All arithmetic is checked, so a route that leaves the range of i64 is
+// reported as an overflow instead of wrapping around.
+#[derive(Clone, Copy)]
+pub struct Exact {
+    pub east: i64,
+    pub north: i64,
+}
+
+impl Vector for Exact {
+    // Wide enough for the distance to any i64 position.
+    type Distance = u128;
+
+    fn translate(self, east: i64, north: i64) -> Result<Exact, InputError> {
+        return Ok(Exact {
+            east: self.east.checked_add(east).ok_or(InputError::Overflow)?,
+            north: self.north.checked_add(north).ok_or(InputError::Overflow)?,
+        });
+    }
+
+    fn add_scaled(self, other: Exact, factor: i64) -> Result<Exact, InputError> {
+        let scale = |value: i64| value.checked_mul(factor).ok_or(InputError::Overflow);
+        return self.translate(scale(other.east)?, scale(other.north)?);
+    }
+
+    fn rotate(self, degrees: i64) -> Result<Exact, InputError> {
+        if degrees % 90 != 0 {
+            return Err(InputError::InvalidAngle(degrees.unsigned_abs()));
+        }
+        let mut rotated = self;
+        for _ in 0..(degrees / 90).rem_euclid(4) {
+            rotated = Exact {
+                east: rotated.north.checked_neg().ok_or(InputError::Overflow)?,
+                north: rotated.east,
+            };
+        }
+        return Ok(rotated);
+    }
+
+    fn to_f64(self) -> (f64, f64) {
+        return (self.east as f64, self.north as f64);
+    }
+
+    fn manhattan_distance(self) -> u128 {
+        return self.east.unsigned_abs() as u128 + self.north.unsigned_abs() as u128;
+    }
+}
+
+// Floating point coordinates, which can be rotated by any angle. After every
+// operation, coordinates within `tolerance` of an integer are snapped to it
+// so that rounding errors from trigonometry don't accumulate over long
+// routes.
+#[derive(Clone, Copy)]
+pub struct Free {
+    pub east: f64,
+    pub north: f64,
+    pub tolerance: f64,
+}
+
+impl Free {
+    pub fn new(east: f64, north: f64, tolerance: f64) -> Free {
+        return Free {
+            east,
+            north,
+            tolerance,
+        };
+    }
+
+    fn snapped(self, east: f64, north: f64) -> Free {
+        let snap = |value: f64| {
+            if (value - value.round()).abs() <= self.tolerance {
+                value.round()
+            } else {
+                value
+            }
+        };
+        return Free::new(snap(east), snap(north), self.tolerance);
+    }
+}
+
+impl Vector for Free {
+    type Distance = f64;
+
+    fn translate(self, east: i64, north: i64) -> Result<Free, InputError> {
+        return Ok(self.snapped(self.east + east as f64, self.north + north as f64));
+    }
+
+    fn add_scaled(self, other: Free, factor: i64) -> Result<Free, InputError> {
+        let factor = factor as f64;
+        return Ok(self.snapped(
+            self.east + other.east * factor,
+            self.north + other.north * factor,
+        ));
+    }
+
+    fn rotate(self, degrees: i64) -> Result<Free, InputError> {
+        let (sin, cos) = (degrees as f64).to_radians().sin_cos();
+        return Ok(self.snapped(
+            self.east * cos - self.north * sin,
+            self.east * sin + self.north * cos,
+        ));
+    }
+
+    fn to_f64(self) -> (f64, f64) {
+        return (self.east, self.north);
+    }
+
+    fn manhattan_distance(self) -> f64 {
+        return self.east.abs() + self.north.abs();
+    }
+}
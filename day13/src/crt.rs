@@ -0,0 +1,137 @@
+// Chinese Remainder Theorem solver for systems whose moduli aren't
+// necessarily pairwise coprime, as described here:
+// https://cp-algorithms.com/algebra/chinese-remainder-theorem.html#solution-for-not-coprime-moduli
+//
+// Congruences are merged one at a time. Merging x ≡ a1 (mod m1) with
+// x ≡ a2 (mod m2) has a solution iff g = gcd(m1, m2) divides a2 - a1, in
+// which case x = a1 + m1 * k where
+//
+// k ≡ (a2 - a1) / g * inverse(m1 / g) (mod m2 / g)
+//
+// and the merged modulus is lcm(m1, m2). All arithmetic is checked, so an
+// overflow is reported instead of silently producing a wrong answer.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum CrtError {
+    // A modulus that isn't positive, with the index of its congruence.
+    InvalidModulus(usize),
+    // The congruence at this index contradicts the ones before it.
+    Inconsistent(usize),
+    // The combined modulus or an intermediate value doesn't fit in an i128.
+    Overflow,
+}
+
+impl fmt::Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrtError::InvalidModulus(i) => write!(f, "congruence {} has a non-positive modulus", i),
+            CrtError::Inconsistent(i) => {
+                write!(f, "congruence {} contradicts the ones before it", i)
+            }
+            CrtError::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+// Solves x ≡ residue (mod modulus) for every (residue, modulus) pair,
+// returning the smallest non-negative solution x together with the combined
+// modulus: every solution is x plus a multiple of it. Residues may be
+// negative or larger than their modulus.
+pub fn solve(congruences: &[(i128, i128)]) -> Result<(i128, i128), CrtError> {
+    let mut x = 0i128;
+    let mut modulus = 1i128;
+    for (i, &(residue, m)) in congruences.iter().enumerate() {
+        if m <= 0 {
+            return Err(CrtError::InvalidModulus(i));
+        }
+        let residue = residue.rem_euclid(m);
+        let (g, inverse, _) = extended_gcd(modulus, m);
+        let difference = residue - x.rem_euclid(m);
+        if difference % g != 0 {
+            return Err(CrtError::Inconsistent(i));
+        }
+        let reduced_m = m / g;
+        // extended_gcd gives modulus * inverse ≡ g (mod m), so inverse is
+        // the inverse of modulus / g modulo m / g.
+        let k = (difference / g)
+            .rem_euclid(reduced_m)
+            .checked_mul(inverse.rem_euclid(reduced_m))
+            .ok_or(CrtError::Overflow)?
+            .rem_euclid(reduced_m);
+        let lcm = modulus.checked_mul(reduced_m).ok_or(CrtError::Overflow)?;
+        x = modulus
+            .checked_mul(k)
+            .and_then(|step| x.checked_add(step))
+            .ok_or(CrtError::Overflow)?
+            .rem_euclid(lcm);
+        modulus = lcm;
+    }
+    return Ok((x, modulus));
+}
+
+// Returns (g, s, t) such that a * s + b * t = g = gcd(a, b), for a, b > 0.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let mut r = (a, b);
+    let mut s = (1i128, 0i128);
+    let mut t = (0i128, 1i128);
+    while r.1 != 0 {
+        let q = r.0 / r.1;
+        r = (r.1, r.0 - q * r.1);
+        s = (s.1, s.0 - q * s.1);
+        t = (t.1, t.0 - q * t.1);
+    }
+    return (r.0, s.0, t.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_schedule() {
+        // 7,13,x,x,59,x,31,19: bus i departs at t + its offset.
+        let buses = [(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)];
+        let congruences: Vec<(i128, i128)> =
+            buses.iter().map(|&(id, offset)| (-offset, id)).collect();
+        assert_eq!(
+            solve(&congruences).unwrap(),
+            (1068781, 7 * 13 * 59 * 31 * 19)
+        );
+    }
+
+    #[test]
+    fn moduli_with_common_factors() {
+        // x ≡ 2 (mod 4) and x ≡ 4 (mod 6) merge into x ≡ 10 (mod 12).
+        assert_eq!(solve(&[(2, 4), (4, 6)]).unwrap(), (10, 12));
+        assert_eq!(solve(&[]).unwrap(), (0, 1));
+    }
+
+    #[test]
+    fn inconsistent_congruences() {
+        assert!(matches!(
+            solve(&[(1, 4), (0, 6)]),
+            Err(CrtError::Inconsistent(1))
+        ));
+    }
+
+    #[test]
+    fn invalid_modulus() {
+        assert!(matches!(
+            solve(&[(1, 3), (0, 0)]),
+            Err(CrtError::InvalidModulus(1))
+        ));
+    }
+
+    #[test]
+    fn overflow() {
+        let big = (1i128 << 100) + 1;
+        assert!(matches!(
+            solve(&[(1, big), (1, big - 2)]),
+            Err(CrtError::Overflow)
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+#![allow(clippy::needless_return)]
+
+use std::fmt;
+use std::io::BufRead;
+use std::iter;
+use std::str::FromStr;
+
+pub mod crt;
+
+use crt::CrtError;
+
+/// The contents of a puzzle input.
+pub struct Notes {
+    /// The earliest timestamp you could depart on a bus.
+    pub earliest: u64,
+    pub timetable: Timetable,
+}
+
+pub fn parse_notes<R: BufRead>(reader: R) -> Result<Notes, ParseError> {
+    let mut lines = reader.lines().map(|line| line.unwrap());
+    let earliest_line = lines.next().ok_or(ParseError::MissingLine)?;
+    let earliest = earliest_line
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| ParseError::InvalidTimestamp(earliest_line.clone()))?;
+    let timetable = lines.next().ok_or(ParseError::MissingLine)?.parse()?;
+    return Ok(Notes {
+        earliest,
+        timetable,
+    });
+}
+
+/// A bus in service, which departs at every multiple of its ID.
+#[derive(Clone, Copy)]
+pub struct Bus {
+    pub id: u64,
+    /// Position of the bus in the timetable, counting `x` entries.
+    pub offset: u64,
+}
+
+/// A departure of one or more buses at the same time.
+pub struct Departure {
+    pub time: u64,
+    pub bus_ids: Vec<u64>,
+}
+
+pub struct Timetable {
+    pub buses: Vec<Bus>,
+}
+
+impl FromStr for Timetable {
+    type Err = ParseError;
+
+    /// Parses a comma-separated list of bus IDs, with `x` for buses that are
+    /// out of service, e.g. `7,13,x,x,59`.
+    fn from_str(s: &str) -> Result<Timetable, ParseError> {
+        let mut buses = Vec::new();
+        for (offset, id) in s.trim().split(',').enumerate() {
+            if id == "x" {
+                continue;
+            }
+            match id.parse::<u64>() {
+                Ok(id) if id > 0 => buses.push(Bus {
+                    id,
+                    offset: offset as u64,
+                }),
+                _ => return Err(ParseError::InvalidBusId(id.to_string())),
+            }
+        }
+        return Ok(Timetable { buses });
+    }
+}
+
+impl Timetable {
+    /// Returns the next `count` departure times of bus `id` at or after
+    /// `time`, stopping early at the last one before `u64::MAX`. Bus IDs are
+    /// positive; see `Timetable::from_str`.
+    pub fn next_departures(id: u64, time: u64, count: usize) -> Vec<u64> {
+        let first = time.div_ceil(id).checked_mul(id);
+        return iter::successors(first, |departure| departure.checked_add(id))
+            .take(count)
+            .collect();
+    }
+
+    /// Returns the bus that departs first after `time`, with its departure
+    /// time, or None if no bus departs before `u64::MAX`. A bus departing
+    /// exactly at `time` has just left, so it's only counted at its next
+    /// departure, as in the puzzle's part 1.
+    pub fn next_bus(&self, time: u64) -> Option<(Bus, u64)> {
+        let after = time.checked_add(1)?;
+        return self
+            .buses
+            .iter()
+            .filter_map(|&bus| {
+                let departure = *Timetable::next_departures(bus.id, after, 1).first()?;
+                Some((bus, departure))
+            })
+            .min_by_key(|&(_, departure)| departure);
+    }
+
+    /// Returns the earliest time t at which each bus in `requirements`
+    /// departs at t plus its offset, as (bus ID, offset) pairs, together
+    /// with the period after which that pattern repeats.
+    pub fn earliest_aligned(requirements: &[(u64, u64)]) -> Result<(i128, i128), CrtError> {
+        let congruences: Vec<(i128, i128)> = requirements
+            .iter()
+            .map(|&(id, offset)| (-(offset as i128), id as i128))
+            .collect();
+        return crt::solve(&congruences);
+    }
+
+    /// Returns every departure between `start` and `end` inclusive, in order
+    /// of time.
+    pub fn departures(&self, start: u64, end: u64) -> Vec<Departure> {
+        let mut times: Vec<(u64, u64)> = self
+            .buses
+            .iter()
+            // Skips buses that don't depart again before u64::MAX.
+            .filter_map(|bus| Some((bus, start.div_ceil(bus.id).checked_mul(bus.id)?)))
+            .flat_map(|(bus, first)| {
+                (first..=end)
+                    .step_by(bus.id as usize)
+                    .map(move |time| (time, bus.id))
+            })
+            .collect();
+        times.sort_unstable();
+        let mut departures: Vec<Departure> = Vec::new();
+        for (time, id) in times {
+            match departures.last_mut() {
+                Some(departure) if departure.time == time => departure.bus_ids.push(id),
+                _ => departures.push(Departure {
+                    time,
+                    bus_ids: vec![id],
+                }),
+            }
+        }
+        return departures;
+    }
+
+    /// Returns the time of each departure between `start` and `end`
+    /// inclusive, paired with the number of minutes until the next one.
+    pub fn gaps(&self, start: u64, end: u64) -> Vec<(u64, u64)> {
+        return self
+            .departures(start, end)
+            .windows(2)
+            .map(|pair| (pair[0].time, pair[1].time - pair[0].time))
+            .collect();
+    }
+
+    /// Renders the minutes from `start` to `end` inclusive the way the
+    /// puzzle does, with a `D` where a bus departs:
+    ///
+    /// ```text
+    /// time   bus 7   bus 13
+    /// 938      .       .
+    /// 939      .       .
+    /// 940      .       D
+    /// ```
+    pub fn board(&self, start: u64, end: u64) -> String {
+        let time_width = end.to_string().len().max(4) + 3;
+        let columns: Vec<String> = self
+            .buses
+            .iter()
+            .map(|bus| format!("bus {}", bus.id))
+            .collect();
+        let column_width = columns.iter().map(|c| c.len()).max().unwrap_or(0) + 3;
+        let mut board = format!("{:<width$}", "time", width = time_width);
+        for column in &columns {
+            board.push_str(&format!("{:<width$}", column, width = column_width));
+        }
+        board = board.trim_end().to_string();
+        board.push('\n');
+        for time in start..=end {
+            let mut row = format!("{:<width$}", time, width = time_width);
+            for bus in &self.buses {
+                let mark = if time % bus.id == 0 { "D" } else { "." };
+                // Centre the mark under the `bus N` heading.
+                row.push_str(&format!("{:^width$}", mark, width = column_width - 3));
+                row.push_str("   ");
+            }
+            board.push_str(row.trim_end());
+            board.push('\n');
+        }
+        return board;
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    MissingLine,
+    InvalidTimestamp(String),
+    InvalidBusId(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingLine => write!(f, "expected a timestamp and a bus list"),
+            ParseError::InvalidTimestamp(s) => write!(f, "invalid timestamp: {}", s),
+            ParseError::InvalidBusId(s) => write!(f, "invalid bus ID: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Notes {
+        return parse_notes("939\n7,13,x,x,59,x,31,19\n".as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn parse_example() {
+        let notes = example();
+        assert_eq!(notes.earliest, 939);
+        let buses: Vec<(u64, u64)> = notes
+            .timetable
+            .buses
+            .iter()
+            .map(|bus| (bus.id, bus.offset))
+            .collect();
+        assert_eq!(buses, vec![(7, 0), (13, 1), (59, 4), (31, 6), (19, 7)]);
+        assert!("7,0".parse::<Timetable>().is_err());
+        assert!(parse_notes("939\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn next_departures() {
+        assert_eq!(Timetable::next_departures(7, 939, 3), vec![945, 952, 959]);
+        assert_eq!(Timetable::next_departures(13, 949, 2), vec![949, 962]);
+        assert_eq!(Timetable::next_departures(13, 939, 0), vec![]);
+        // The last multiple of 7 that fits in a u64 is u64::MAX - 1.
+        assert_eq!(
+            Timetable::next_departures(7, u64::MAX - 5, 3),
+            vec![u64::MAX - 1]
+        );
+        assert_eq!(Timetable::next_departures(7, u64::MAX, 1), vec![]);
+    }
+
+    #[test]
+    fn next_bus() {
+        let notes = example();
+        let (bus, departure) = notes.timetable.next_bus(notes.earliest).unwrap();
+        assert_eq!((bus.id, departure), (59, 944));
+        assert_eq!((departure - notes.earliest) * bus.id, 295);
+        // A bus leaving right at the given time is missed.
+        let (bus, departure) = notes.timetable.next_bus(944).unwrap();
+        assert_eq!((bus.id, departure), (7, 945));
+        assert!(notes.timetable.next_bus(u64::MAX).is_none());
+    }
+
+    #[test]
+    fn earliest_aligned() {
+        let notes = example();
+        let requirements: Vec<(u64, u64)> = notes
+            .timetable
+            .buses
+            .iter()
+            .map(|bus| (bus.id, bus.offset))
+            .collect();
+        let (time, _) = Timetable::earliest_aligned(&requirements).unwrap();
+        assert_eq!(time, 1068781);
+    }
+
+    #[test]
+    fn departures_and_gaps() {
+        let timetable = example().timetable;
+        let departures: Vec<(u64, Vec<u64>)> = timetable
+            .departures(939, 950)
+            .into_iter()
+            .map(|departure| (departure.time, departure.bus_ids))
+            .collect();
+        assert_eq!(
+            departures,
+            vec![
+                (944, vec![59]),
+                (945, vec![7]),
+                (949, vec![13]),
+                (950, vec![19])
+            ]
+        );
+        assert_eq!(timetable.gaps(939, 950), vec![(944, 1), (945, 4), (949, 1)]);
+        let shared: Timetable = "7,13".parse().unwrap();
+        assert_eq!(shared.departures(91, 91)[0].bus_ids, vec![7, 13]);
+        assert_eq!(
+            timetable.gaps(u64::MAX - 8, u64::MAX),
+            vec![(u64::MAX - 8, 4), (u64::MAX - 4, 2), (u64::MAX - 2, 1)]
+        );
+    }
+
+    #[test]
+    fn board() {
+        let timetable = example().timetable;
+        assert_eq!(
+            timetable.board(944, 945),
+            "time   bus 7    bus 13   bus 59   bus 31   bus 19
+944      .        .        D        .        .
+945      D        .        .        .        .
+"
+        );
+    }
+}
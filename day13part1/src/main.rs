@@ -1,22 +1,121 @@
+#![allow(clippy::needless_return)]
+
 use std::env;
 use std::fs::File;
-use std::io::{self, BufRead};
+use std::io;
+use std::process;
+use std::str::FromStr;
+
+use day13::{parse_notes, Timetable};
+
+const USAGE: &str = "usage: day13part1 <path to input text file>
+    [--next <bus ID> <count> [time]] [--align <bus ID>:<offset>,...]
+    [--gaps <start> <end>] [--board <start> <end>]";
+
+enum Query {
+    Next(u64, usize, Option<u64>),
+    Align(Vec<(u64, u64)>),
+    Gaps(u64, u64),
+    Board(u64, u64),
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let filename = &args[1];
-    let file = File::open(filename).unwrap();
-    let lines: Vec<String> = io::BufReader::new(file)
-        .lines()
-        .map(|line| line.unwrap())
-        .collect();
-    let timestamp = lines[0].parse::<u32>().unwrap();
-    let result = lines[1]
-        .split(",")
-        .filter(|&id| id != "x")
-        .map(|id| id.parse::<u32>().unwrap())
-        .map(|id| (id - (timestamp % id), id))
-        .min()
-        .unwrap();
-    println!("{}", result.0 * result.1);
+    if let Err(err) = run() {
+        eprintln!("{}", err);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = Vec::new();
+    let mut queries = Vec::new();
+    let mut arg_iter = env::args().peekable();
+    while let Some(arg) = arg_iter.next() {
+        let mut value = || {
+            arg_iter
+                .next()
+                .ok_or_else(|| format!("{} needs more values", arg))
+        };
+        match arg.as_str() {
+            "--next" => {
+                let id = parse_number("bus ID", &value()?)?;
+                if id == 0 {
+                    return Err("invalid bus ID: 0".to_string());
+                }
+                let count = parse_number("count", &value()?)?;
+                let time = match arg_iter.peek() {
+                    Some(next) if !next.starts_with("--") => {
+                        Some(parse_number("time", &arg_iter.next().unwrap())?)
+                    }
+                    _ => None,
+                };
+                queries.push(Query::Next(id, count, time));
+            }
+            "--align" => {
+                let requirements = value()?
+                    .split(',')
+                    .map(|pair| {
+                        let mut components = pair.splitn(2, ':');
+                        let id = parse_number("bus ID", components.next().unwrap())?;
+                        let offset = parse_number("offset", components.next().unwrap_or("0"))?;
+                        Ok((id, offset))
+                    })
+                    .collect::<Result<_, String>>()?;
+                queries.push(Query::Align(requirements));
+            }
+            "--gaps" => queries.push(Query::Gaps(
+                parse_number("start", &value()?)?,
+                parse_number("end", &value()?)?,
+            )),
+            "--board" => queries.push(Query::Board(
+                parse_number("start", &value()?)?,
+                parse_number("end", &value()?)?,
+            )),
+            _ => args.push(arg),
+        }
+    }
+    let filename = args.get(1).ok_or("missing input file")?;
+    let file =
+        File::open(filename).map_err(|err| format!("couldn't open {}: {}", filename, err))?;
+    let notes = parse_notes(io::BufReader::new(file)).map_err(|err| err.to_string())?;
+    let timetable = &notes.timetable;
+
+    if queries.is_empty() {
+        let (bus, departure) = timetable
+            .next_bus(notes.earliest)
+            .ok_or_else(|| format!("no bus departs after {}", notes.earliest))?;
+        println!("{}", (departure - notes.earliest) * bus.id);
+        return Ok(());
+    }
+    for query in queries {
+        match query {
+            Query::Next(id, count, time) => {
+                let departures: Vec<String> =
+                    Timetable::next_departures(id, time.unwrap_or(notes.earliest), count)
+                        .iter()
+                        .map(|t| t.to_string())
+                        .collect();
+                println!("bus {}: {}", id, departures.join(", "));
+            }
+            Query::Align(requirements) => {
+                let (time, period) =
+                    Timetable::earliest_aligned(&requirements).map_err(|err| err.to_string())?;
+                println!("{} (repeats every {})", time, period);
+            }
+            Query::Gaps(start, end) => {
+                for (time, gap) in timetable.gaps(start, end) {
+                    println!("{} -> {} ({} min)", time, time + gap, gap);
+                }
+            }
+            Query::Board(start, end) => print!("{}", timetable.board(start, end)),
+        }
+    }
+    return Ok(());
+}
+
+fn parse_number<T: FromStr>(name: &str, s: &str) -> Result<T, String> {
+    return s
+        .parse()
+        .map_err(|_| format!("{} needs a number, not {}", name, s));
 }
@@ -1,22 +1,39 @@
+#![allow(clippy::needless_return)]
+
 use std::env;
 use std::fs::File;
-use std::io::{self, BufRead};
+use std::io;
+use std::process;
+
+use day13::crt::CrtError;
+use day13::{parse_notes, Bus, Timetable};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
     let file = File::open(filename).unwrap();
-    let lines: Vec<String> = io::BufReader::new(file)
-        .lines()
-        .map(|line| line.unwrap())
-        .collect();
-    let pairs: Vec<(i64, i64)> = lines[1]
-        .split(",")
-        .enumerate()
-        .filter(|&(_, id)| id != "x")
-        .map(|(idx, id)| (idx as i64, id.parse::<i64>().unwrap()))
-        .collect();
-    print!("{}", garners_algorithm(pairs));
+    let notes = match parse_notes(io::BufReader::new(file)) {
+        Ok(notes) => notes,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+    let buses = &notes.timetable.buses;
+    match earliest_timestamp(buses) {
+        Ok((timestamp, _)) => println!("{}", timestamp),
+        Err(CrtError::Inconsistent(i)) => {
+            eprintln!(
+                "no timestamp exists: bus {} at offset {} conflicts with the buses before it",
+                buses[i].id, buses[i].offset
+            );
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
 }
 
 // m -> number of minutes after
@@ -34,49 +51,14 @@ fn main() {
 //
 // This is in the form a_{i} = a % p_{i} where a_{i} = b_{i} - m{i},
 // a = t, and p_{i} = b{i}, which can be solved using the Chinese
-// Remainder Theorem and Garner's Algorithm as described here:
-// https://cp-algorithms.com/algebra/chinese-remainder-theorem.html
+// Remainder Theorem (see `day13::crt`). Bus IDs don't need to be coprime, but
+// if two of them share a factor their offsets have to agree modulo it.
 //
-// Each pair in the vector passed to this function is an (index, bus ID)
-// pair where the index is m_{i} in the above equations, since each bus ID
-// in the list is supposed to come 1 minute after the previous bus ID in
-// the list.
-fn garners_algorithm(pairs: Vec<(i64, i64)>) -> i64 {
-    let k = pairs.len();
-    let mut x = vec![0; k];
-    for i in 0..k {
-        x[i] = pairs[i].1 - pairs[i].0;
-        for j in 0..i {
-            x[i] = mod_inv(pairs[j].1, pairs[i].1) * (x[i] - x[j]);
-            x[i] = x[i] % pairs[i].1;
-            if x[i] < 0 {
-                x[i] += pairs[i].1;
-            }
-        }
-    }
-
-    let mut ts = 0i64;
-    for i in 0..k {
-        let mut product = x[i];
-        for j in 0..i {
-            product *= pairs[j].1;
-        }
-        ts += product;
-    }
-
-    return ts;
-}
-
-// https://rosettacode.org/wiki/Modular_inverse#Rust
-fn mod_inv(a: i64, module: i64) -> i64 {
-    let mut mn = (module, a);
-    let mut xy = (0, 1);
-    while mn.1 != 0 {
-        xy = (xy.1, xy.0 - (mn.0 / mn.1) * xy.1);
-        mn = (mn.1, mn.0 % mn.1);
-    }
-    while xy.0 < 0 {
-        xy.0 += module;
-    }
-    xy.0
+// The offset of each bus passed to this function is m_{i} in the above
+// equations, since each bus ID in the list is supposed to come 1 minute
+// after the previous bus ID in the list. Returns the earliest timestamp
+// and the period after which the pattern repeats.
+fn earliest_timestamp(buses: &[Bus]) -> Result<(i128, i128), CrtError> {
+    let requirements: Vec<(u64, u64)> = buses.iter().map(|bus| (bus.id, bus.offset)).collect();
+    return Timetable::earliest_aligned(&requirements);
 }
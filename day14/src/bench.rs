@@ -0,0 +1,98 @@
+// Compares the string-based address decoder that day14part2 originally
+// used against the bitmask decoder in `mask`, on the memory stores of a
+// program. Only stores under 36-bit masks are used, since that's all the
+// string-based decoder supports.
+
+use crate::mask::Bitmask;
+use crate::Instruction;
+use std::time::Instant;
+
+pub fn run(program: &[Instruction]) {
+    let mut stores: Vec<(String, u64)> = Vec::new();
+    let mut bitmasks: Vec<Bitmask> = Vec::new();
+    let mut current_bitmask = Bitmask::default();
+    for instruction in program {
+        match *instruction {
+            Instruction::Mask(bitmask) => current_bitmask = bitmask,
+            Instruction::Write { address, .. } => {
+                if current_bitmask.width == 36 {
+                    stores.push((current_bitmask.to_string(), address));
+                    bitmasks.push(current_bitmask);
+                }
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let string_count: usize = stores
+        .iter()
+        .map(|(bitmask, address)| bitmasked_addresses(*address, bitmask).len())
+        .sum();
+    let string_elapsed = start.elapsed();
+
+    let start = Instant::now();
+    let bitmask_count: usize = stores
+        .iter()
+        .zip(&bitmasks)
+        .map(|((_, address), bitmask)| bitmask.addresses(*address).count())
+        .sum();
+    let bitmask_elapsed = start.elapsed();
+
+    for ((bitmask_str, address), bitmask) in stores.iter().zip(&bitmasks) {
+        let mut expected = bitmasked_addresses(*address, bitmask_str);
+        let mut actual: Vec<u64> = bitmask.addresses(*address).collect();
+        expected.sort_unstable();
+        actual.sort_unstable();
+        assert_eq!(
+            expected, actual,
+            "decoders disagree on address {} with mask {}",
+            address, bitmask_str
+        );
+    }
+
+    println!(
+        "{} memory stores, {} addresses",
+        stores.len(),
+        bitmask_count
+    );
+    println!("string decoder:  {:?}", string_elapsed);
+    println!("bitmask decoder: {:?}", bitmask_elapsed);
+    if string_count == bitmask_count && !bitmask_elapsed.is_zero() {
+        println!(
+            "speedup: {:.1}x",
+            string_elapsed.as_secs_f64() / bitmask_elapsed.as_secs_f64()
+        );
+    }
+}
+
+fn bitmasked_addresses(address: u64, bitmask: &str) -> Vec<u64> {
+    let masked_addr: String = format!("{:036b}", address)
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match bitmask.chars().nth(i).unwrap() {
+            'X' => 'X',
+            '0' => c,
+            '1' => '1',
+            v => v,
+        })
+        .collect();
+    let floating_count = masked_addr.chars().filter(|&c| c == 'X').count();
+    (0..2u32.pow(floating_count as u32))
+        .map(|combo| {
+            let mut x_index = 0usize;
+            let combo_str = format!("{:0width$b}", combo, width = floating_count);
+            let new_addr: String = masked_addr
+                .chars()
+                .map(|c| match c {
+                    'X' => {
+                        let new_c = combo_str.chars().nth(x_index).unwrap();
+                        x_index += 1;
+                        return new_c;
+                    }
+                    ch => ch,
+                })
+                .collect();
+            return u64::from_str_radix(&new_addr, 2).unwrap();
+        })
+        .collect()
+}
@@ -0,0 +1,68 @@
+use crate::{bench, dump, execute, execute_symbolic, parse_program, sum_memory, Radix, Version};
+use std::env;
+use std::fs::File;
+use std::io;
+use std::process;
+
+const USAGE: &str = "usage: <path to input text file> [--version 1|2]
+    [--dump hex|binary] [--symbolic] [--bench]";
+
+/// Runs the docking program in the input file through the `default_version`
+/// decoder, or the one picked with --version, and prints the sum of memory.
+/// Any error is printed with the usage text before exiting with status 1.
+pub fn run(default_version: Version) {
+    if let Err(err) = run_args(default_version) {
+        eprintln!("{}", err);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run_args(default_version: Version) -> Result<(), String> {
+    let mut args = Vec::new();
+    let mut version = default_version;
+    let mut dump_radix: Option<Radix> = None;
+    let mut symbolic = false;
+    let mut run_bench = false;
+    let mut arg_iter = env::args();
+    while let Some(arg) = arg_iter.next() {
+        let mut value = || {
+            arg_iter
+                .next()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--version" => version = value()?.parse()?,
+            "--dump" => dump_radix = Some(value()?.parse()?),
+            "--symbolic" => symbolic = true,
+            "--bench" => run_bench = true,
+            _ => args.push(arg),
+        }
+    }
+    let filename = args.get(1).ok_or("missing input file")?;
+    let file =
+        File::open(filename).map_err(|err| format!("couldn't open {}: {}", filename, err))?;
+    let program = parse_program(io::BufReader::new(file)).map_err(|err| err.to_string())?;
+
+    if run_bench {
+        bench::run(&program);
+        return Ok(());
+    }
+    if symbolic {
+        if let Version::V1 = version {
+            return Err("--symbolic only applies to the version 2 decoder".to_string());
+        }
+        if dump_radix.is_some() {
+            return Err("--symbolic can't be combined with --dump".to_string());
+        }
+        let sum = execute_symbolic(&program).ok_or("the sum of memory doesn't fit in 128 bits")?;
+        println!("{}", sum);
+        return Ok(());
+    }
+    let memory = execute(&program, version);
+    if let Some(radix) = dump_radix {
+        print!("{}", dump(&memory, radix));
+    }
+    println!("{}", sum_memory(&memory));
+    return Ok(());
+}
@@ -0,0 +1,215 @@
+#![allow(clippy::needless_return)]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+pub mod bench;
+pub mod cli;
+pub mod mask;
+pub mod symbolic;
+
+pub use mask::Bitmask;
+pub use symbolic::SymbolicMemory;
+
+pub enum Instruction {
+    /// `mask = <mask>`
+    Mask(Bitmask),
+    /// `mem[<address>] = <value>`
+    Write { address: u64, value: u64 },
+}
+
+/// Parses an initialization program. Blank lines are skipped, and any other
+/// line that isn't a mask or a memory write is an error.
+pub fn parse_program<R: BufRead>(reader: R) -> Result<Vec<Instruction>, ParseError> {
+    let mut program = Vec::new();
+    for (i, line) in reader.lines().map(|line| line.unwrap()).enumerate() {
+        // Tolerate CRLF line endings.
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        if line.trim().is_empty() {
+            continue;
+        }
+        program.push(
+            parse_instruction(line)
+                .ok_or_else(|| ParseError::InvalidInstruction(i + 1, line.to_string()))?,
+        );
+    }
+    return Ok(program);
+}
+
+pub fn parse_instruction(line: &str) -> Option<Instruction> {
+    if let Some(mask) = line.strip_prefix("mask = ") {
+        return Bitmask::parse(mask).map(Instruction::Mask);
+    }
+    let rest = line.strip_prefix("mem[")?;
+    let mut components = rest.splitn(2, "] = ");
+    let address = components.next()?.parse::<u64>().ok()?;
+    let value = components.next()?.parse::<u64>().ok()?;
+    return Some(Instruction::Write { address, value });
+}
+
+/// The version of the decoder chip, which decides what a mask applies to.
+#[derive(Clone, Copy)]
+pub enum Version {
+    /// Masks the values written to memory.
+    V1,
+    /// Masks the addresses written to, with floating bits writing to every
+    /// combination of addresses.
+    V2,
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Version, String> {
+        return match s {
+            "1" | "v1" => Ok(Version::V1),
+            "2" | "v2" => Ok(Version::V2),
+            _ => Err(format!("unknown decoder version: {}", s)),
+        };
+    }
+}
+
+/// Runs a program and returns the final contents of memory.
+pub fn execute(program: &[Instruction], version: Version) -> HashMap<u64, u64> {
+    let mut memory: HashMap<u64, u64> = HashMap::new();
+    let mut current_bitmask = Bitmask::default();
+    for instruction in program {
+        match *instruction {
+            Instruction::Mask(bitmask) => current_bitmask = bitmask,
+            Instruction::Write { address, value } => match version {
+                Version::V1 => {
+                    let _ = memory.insert(address, current_bitmask.mask_value(value));
+                }
+                Version::V2 => current_bitmask.addresses(address).for_each(|address| {
+                    let _ = memory.insert(address, value);
+                }),
+            },
+        }
+    }
+    return memory;
+}
+
+/// The sum of every value in memory. There are at most 2^64 addresses, so
+/// it always fits in a u128.
+pub fn sum_memory(memory: &HashMap<u64, u64>) -> u128 {
+    return memory.values().map(|&value| value as u128).sum();
+}
+
+/// Runs a program with the version 2 decoder without enumerating floating
+/// addresses, and returns the sum of memory, or None if it doesn't fit in a
+/// u128.
+pub fn execute_symbolic(program: &[Instruction]) -> Option<u128> {
+    let mut memory = SymbolicMemory::new();
+    let mut current_bitmask = Bitmask::default();
+    for instruction in program {
+        match *instruction {
+            Instruction::Mask(bitmask) => current_bitmask = bitmask,
+            Instruction::Write { address, value } => memory.write(&current_bitmask, address, value),
+        }
+    }
+    return memory.sum();
+}
+
+pub enum Radix {
+    Hex,
+    Binary,
+}
+
+impl FromStr for Radix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Radix, String> {
+        return match s {
+            "hex" => Ok(Radix::Hex),
+            "binary" => Ok(Radix::Binary),
+            _ => Err(format!("unknown dump format: {}", s)),
+        };
+    }
+}
+
+/// Lists the contents of memory sorted by address, one `mem[<address>] =
+/// <value>` line per address, with addresses and values padded to 36 bits.
+pub fn dump(memory: &HashMap<u64, u64>, radix: Radix) -> String {
+    let mut addresses: Vec<&u64> = memory.keys().collect();
+    addresses.sort_unstable();
+    let mut output = String::new();
+    for address in addresses {
+        let value = memory[address];
+        output.push_str(&match radix {
+            Radix::Hex => format!("mem[0x{:09x}] = 0x{:09x}\n", address, value),
+            Radix::Binary => format!("mem[0b{:036b}] = 0b{:036b}\n", address, value),
+        });
+    }
+    return output;
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    /// A line that isn't a valid mask or memory write, with its (1-based)
+    /// line number.
+    InvalidInstruction(usize, String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidInstruction(line, s) => {
+                write!(f, "line {}: invalid instruction: {}", line, s)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(program: &str) -> Vec<Instruction> {
+        return parse_program(program.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn version_1_example() {
+        let program = parse(
+            "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0",
+        );
+        assert_eq!(sum_memory(&execute(&program, Version::V1)), 165);
+    }
+
+    #[test]
+    fn version_2_example() {
+        let program = parse(
+            "mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1",
+        );
+        assert_eq!(sum_memory(&execute(&program, Version::V2)), 208);
+        assert_eq!(execute_symbolic(&program), Some(208));
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let program = parse("mask = 000000000000000000000000000000X1001X\r\nmem[42] = 100\r\n");
+        assert_eq!(execute_symbolic(&program), Some(400));
+    }
+
+    #[test]
+    fn sum_wider_than_u64() {
+        let program = parse(
+            "mask = 000000000000000000000000000000000000
+mem[0] = 18446744073709551615
+mem[1] = 18446744073709551615",
+        );
+        let expected = 2 * u64::MAX as u128;
+        assert_eq!(sum_memory(&execute(&program, Version::V2)), expected);
+        assert_eq!(execute_symbolic(&program), Some(expected));
+    }
+}
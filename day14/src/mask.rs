@@ -0,0 +1,159 @@
+// Masks represented as bitmasks instead of strings.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Default)]
+pub struct Bitmask {
+    // Bits forced to 1 (`1` in the mask).
+    pub ones: u64,
+    // Floating bits (`X` in the mask).
+    pub floating: u64,
+    // Number of bits the mask covers. Bits above it are left as is by both
+    // decoders, so the default mask of width 0 changes nothing.
+    pub width: u32,
+}
+
+impl Bitmask {
+    // Parses a mask of 1 to 64 `0`, `1` and `X` characters, most significant
+    // bit first. Address bits above the width of the mask are left as is.
+    pub fn parse(mask: &str) -> Option<Bitmask> {
+        if mask.is_empty() || mask.len() > 64 {
+            return None;
+        }
+        let mut bitmask = Bitmask {
+            ones: 0,
+            floating: 0,
+            width: mask.len() as u32,
+        };
+        for c in mask.chars() {
+            bitmask.ones <<= 1;
+            bitmask.floating <<= 1;
+            match c {
+                '0' => {}
+                '1' => bitmask.ones |= 1,
+                'X' => bitmask.floating |= 1,
+                _ => return None,
+            }
+        }
+        return Some(bitmask);
+    }
+
+    // Bits forced to 0 (`0` in the mask).
+    pub fn zeros(&self) -> u64 {
+        let covered = u64::MAX.checked_shr(64 - self.width).unwrap_or(0);
+        return covered & !self.ones & !self.floating;
+    }
+
+    // Applies the mask to a value the way the version 1 decoder does: `0`
+    // and `1` overwrite the bit and `X` leaves it unchanged.
+    pub fn mask_value(&self, value: u64) -> u64 {
+        return (value & !self.zeros()) | self.ones;
+    }
+
+    // Returns every address that `address` decodes to under the version 2
+    // decoder.
+    pub fn addresses(&self, address: u64) -> FloatingAddresses {
+        return FloatingAddresses {
+            base: (address | self.ones) & !self.floating,
+            floating: self.floating,
+            subset: Some(0),
+        };
+    }
+}
+
+impl fmt::Display for Bitmask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in (0..self.width).rev() {
+            let bit = 1u64 << i;
+            let c = if self.floating & bit != 0 {
+                'X'
+            } else if self.ones & bit != 0 {
+                '1'
+            } else {
+                '0'
+            };
+            write!(f, "{}", c)?;
+        }
+        return Ok(());
+    }
+}
+
+// Iterates over the 2^k addresses for a mask with k floating bits by
+// enumerating every subset of the floating bits: `(subset - floating) &
+// floating` is the next subset in increasing order, wrapping to 0 after the
+// full set.
+pub struct FloatingAddresses {
+    base: u64,
+    floating: u64,
+    subset: Option<u64>,
+}
+
+impl Iterator for FloatingAddresses {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let subset = self.subset?;
+        let next = subset.wrapping_sub(self.floating) & self.floating;
+        self.subset = if next == 0 { None } else { Some(next) };
+        return Some(self.base | subset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(s: &str) -> Bitmask {
+        return Bitmask::parse(s).unwrap();
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let m = mask("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X");
+        assert_eq!(m.to_string(), "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X");
+        assert_eq!(m.ones, 0b100_0000);
+        assert_eq!(m.zeros(), 0b10);
+        assert_eq!(mask(&"1".repeat(64)).zeros(), 0);
+        for s in &["", "10a", &"0".repeat(65)] {
+            assert!(Bitmask::parse(s).is_none(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn mask_values() {
+        let m = mask("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X");
+        assert_eq!(m.mask_value(11), 73);
+        assert_eq!(m.mask_value(101), 101);
+        assert_eq!(m.mask_value(0), 64);
+        // Bits above a narrow mask are left alone.
+        assert_eq!(mask("0").mask_value(0b11), 0b10);
+    }
+
+    #[test]
+    fn floating_addresses() {
+        let mut addresses: Vec<u64> = mask("000000000000000000000000000000X1001X")
+            .addresses(42)
+            .collect();
+        addresses.sort_unstable();
+        assert_eq!(addresses, vec![26, 27, 58, 59]);
+        let addresses: Vec<u64> = mask("00000000000000000000000000000000X0XX")
+            .addresses(26)
+            .collect();
+        assert_eq!(addresses, vec![16, 17, 18, 19, 24, 25, 26, 27]);
+        assert_eq!(
+            Bitmask::default().addresses(5).collect::<Vec<u64>>(),
+            vec![5]
+        );
+    }
+
+    #[test]
+    fn every_floating_bit() {
+        let m = mask(&"X".repeat(64));
+        assert_eq!(
+            m.addresses(0).take(4).collect::<Vec<u64>>(),
+            vec![0, 1, 2, 3]
+        );
+        let mut addresses = m.addresses(u64::MAX);
+        assert_eq!(addresses.nth(3), Some(3));
+    }
+}
@@ -0,0 +1,147 @@
+// Version 2 decoder memory that never enumerates floating addresses. Each
+// write is kept as a ternary address pattern, and when a later write
+// overlaps an earlier one, the overlap is subtracted from the earlier
+// pattern. The patterns left at the end are disjoint, so the sum of memory
+// is the sum of each value times the number of addresses in its pattern.
+
+use crate::mask::Bitmask;
+
+// The addresses `fixed | subset` for every subset of `floating`.
+#[derive(Clone, Copy)]
+struct Pattern {
+    fixed: u64,
+    floating: u64,
+}
+
+impl Pattern {
+    fn overlaps(&self, other: &Pattern) -> bool {
+        let fixed_in_both = !self.floating & !other.floating;
+        return (self.fixed ^ other.fixed) & fixed_in_both == 0;
+    }
+
+    // Splits `self` into disjoint patterns covering the addresses that
+    // aren't in `other`. Each floating bit of `self` that is fixed in `other`
+    // yields one piece, where that bit takes the value `other` doesn't have
+    // and the bits before it take the value `other` does have.
+    fn subtract(&self, other: &Pattern) -> Vec<Pattern> {
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+        let mut pieces = Vec::new();
+        let mut remaining = *self;
+        let mut split_bits = self.floating & !other.floating;
+        while split_bits != 0 {
+            let bit = split_bits & split_bits.wrapping_neg();
+            split_bits &= !bit;
+            remaining.floating &= !bit;
+            pieces.push(Pattern {
+                fixed: remaining.fixed | (!other.fixed & bit),
+                floating: remaining.floating,
+            });
+            remaining.fixed |= other.fixed & bit;
+        }
+        return pieces;
+    }
+
+    fn len(&self) -> u128 {
+        return 1u128 << self.floating.count_ones();
+    }
+}
+
+#[derive(Default)]
+pub struct SymbolicMemory {
+    writes: Vec<(Pattern, u64)>,
+}
+
+impl SymbolicMemory {
+    pub fn new() -> SymbolicMemory {
+        return SymbolicMemory { writes: Vec::new() };
+    }
+
+    pub fn write(&mut self, bitmask: &Bitmask, address: u64, value: u64) {
+        let pattern = Pattern {
+            fixed: (address | bitmask.ones) & !bitmask.floating,
+            floating: bitmask.floating,
+        };
+        self.writes = self
+            .writes
+            .iter()
+            .flat_map(|(existing, existing_value)| {
+                existing
+                    .subtract(&pattern)
+                    .into_iter()
+                    .map(move |piece| (piece, *existing_value))
+            })
+            .collect();
+        if value != 0 {
+            self.writes.push((pattern, value));
+        }
+    }
+
+    // The sum of every value in memory. This can exceed 64 bits when masks
+    // have many floating bits, and is None if it doesn't fit in 128.
+    pub fn sum(&self) -> Option<u128> {
+        return self.writes.iter().try_fold(0u128, |sum, (pattern, value)| {
+            sum.checked_add(pattern.len().checked_mul(*value as u128)?)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Brute force sum of the same writes, enumerating every address.
+    fn enumerated_sum(writes: &[(&str, u64, u64)]) -> u128 {
+        let mut memory = std::collections::HashMap::new();
+        for &(mask, address, value) in writes {
+            for address in Bitmask::parse(mask).unwrap().addresses(address) {
+                let _ = memory.insert(address, value);
+            }
+        }
+        return memory.values().map(|&value| value as u128).sum();
+    }
+
+    fn symbolic_sum(writes: &[(&str, u64, u64)]) -> Option<u128> {
+        let mut memory = SymbolicMemory::new();
+        for &(mask, address, value) in writes {
+            memory.write(&Bitmask::parse(mask).unwrap(), address, value);
+        }
+        return memory.sum();
+    }
+
+    #[test]
+    fn matches_enumeration() {
+        let programs: [&[(&str, u64, u64)]; 3] = [
+            &[("X1001X", 42, 100), ("X0XX", 26, 1)],
+            &[
+                ("XX0X1X", 3, 7),
+                ("1XX0XX", 50, 2),
+                ("XXXXXX", 9, 0),
+                ("X1X1X1", 0, 5),
+            ],
+            &[
+                ("XXXX", 0, 1),
+                ("0X1X", 0, 3),
+                ("XX10", 1, 4),
+                ("0000", 5, 9),
+            ],
+        ];
+        for writes in programs.iter() {
+            assert_eq!(symbolic_sum(writes), Some(enumerated_sum(writes)));
+        }
+    }
+
+    #[test]
+    fn many_floating_bits() {
+        let every_address = "X".repeat(64);
+        assert_eq!(symbolic_sum(&[(&every_address, 0, 3)]), Some(3 << 64));
+        let mut half = "X".repeat(63);
+        half.insert(0, '1');
+        assert_eq!(
+            symbolic_sum(&[(&every_address, 0, 3), (&half, 0, 1)]),
+            Some((3 << 63) + (1 << 63))
+        );
+        assert_eq!(symbolic_sum(&[(&every_address, 0, 0)]), Some(0));
+    }
+}
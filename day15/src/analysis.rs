@@ -0,0 +1,146 @@
+// Statistics about the sequence the memory game produces (a Van Eck
+// sequence when the only starting number is 0), written as CSV for
+// plotting.
+
+use crate::MemoryGame;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+pub enum Analysis {
+    // One row per turn: the number spoken, its gap since it was last
+    // spoken (empty on its first occurrence), the largest number spoken so
+    // far and the count of distinct numbers spoken so far.
+    Timeline,
+    // The turn on which each number was first spoken, by number.
+    FirstOccurrences,
+    // How many times each gap between repeats of a number occurred.
+    Gaps,
+}
+
+impl FromStr for Analysis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Analysis, String> {
+        return match s {
+            "timeline" => Ok(Analysis::Timeline),
+            "first-occurrences" => Ok(Analysis::FirstOccurrences),
+            "gaps" => Ok(Analysis::Gaps),
+            _ => Err(format!("unknown analysis: {}", s)),
+        };
+    }
+}
+
+// Plays `game` to the end, writing `analysis` to `out`. Timeline rows are
+// only written for every `every`th turn and the final turn.
+pub fn analyze<W: Write>(
+    out: &mut W,
+    mut game: MemoryGame,
+    analysis: Analysis,
+    every: u32,
+) -> io::Result<()> {
+    let mut first_turns: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut gaps: BTreeMap<u32, u64> = BTreeMap::new();
+    let mut largest = 0;
+    let mut distinct = 0;
+    let turns = game.turns();
+    if let Analysis::Timeline = analysis {
+        writeln!(out, "turn,number,gap,largest,distinct")?;
+    }
+    let mut turn = 0;
+    while let Some(number) = game.next() {
+        turn += 1;
+        let gap = game.previous_turn(number).map(|previous| turn - previous);
+        match gap {
+            Some(gap) => *gaps.entry(gap).or_insert(0) += 1,
+            None => {
+                distinct += 1;
+                if let Analysis::FirstOccurrences = analysis {
+                    first_turns.insert(number, turn);
+                }
+            }
+        }
+        largest = largest.max(number);
+        if let Analysis::Timeline = analysis {
+            if turn.is_multiple_of(every) || turn == turns {
+                let gap = gap.map_or(String::new(), |gap| gap.to_string());
+                writeln!(out, "{},{},{},{},{}", turn, number, gap, largest, distinct)?;
+            }
+        }
+    }
+    match analysis {
+        Analysis::Timeline => {}
+        Analysis::FirstOccurrences => {
+            writeln!(out, "number,first_turn")?;
+            for (number, turn) in first_turns {
+                writeln!(out, "{},{}", number, turn)?;
+            }
+        }
+        Analysis::Gaps => {
+            writeln!(out, "gap,count")?;
+            for (gap, count) in gaps {
+                writeln!(out, "{},{}", gap, count)?;
+            }
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Analyzes the first 10 turns of the 0,3,6 example:
+    // 0, 3, 6, 0, 3, 3, 1, 0, 4, 0.
+    fn example(analysis: &str, every: u32) -> String {
+        let game = MemoryGame::new(&[0, 3, 6], 10).unwrap();
+        let mut out = Vec::new();
+        analyze(&mut out, game, analysis.parse().unwrap(), every).unwrap();
+        return String::from_utf8(out).unwrap();
+    }
+
+    #[test]
+    fn timeline() {
+        assert_eq!(
+            example("timeline", 4),
+            "turn,number,gap,largest,distinct
+4,0,3,6,3
+8,0,4,6,4
+10,0,2,6,5
+"
+        );
+        assert!(example("timeline", 1).starts_with("turn,number,gap,largest,distinct\n1,0,,0,1\n"));
+    }
+
+    #[test]
+    fn first_occurrences() {
+        assert_eq!(
+            example("first-occurrences", 1),
+            "number,first_turn
+0,1
+1,7
+3,2
+4,9
+6,3
+"
+        );
+    }
+
+    #[test]
+    fn gaps() {
+        assert_eq!(
+            example("gaps", 1),
+            "gap,count
+1,1
+2,1
+3,2
+4,1
+"
+        );
+    }
+
+    #[test]
+    fn unknown_analysis() {
+        assert!("histogram".parse::<Analysis>().is_err());
+    }
+}
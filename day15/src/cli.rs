@@ -0,0 +1,120 @@
+use crate::analysis::{self, Analysis};
+use crate::{parse_start_numbers, MemoryGame};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+const USAGE: &str = "usage: (<path to input text file> | --start <numbers>) [turns]
+    [--stream <path> | --analyze timeline|first-occurrences|gaps] [--every <n>]";
+
+/// Plays the memory game from the starting numbers in the input file or
+/// --start and prints the number spoken on turn `default_turns`, unless the
+/// command line names another turn. Errors are printed with the usage text,
+/// and the process exits with status 1.
+pub fn run(default_turns: u32) {
+    if let Err(err) = run_args(default_turns) {
+        eprintln!("{}", err);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run_args(default_turns: u32) -> Result<(), String> {
+    let mut args = Vec::new();
+    let mut stream_path: Option<String> = None;
+    let mut every = 1u32;
+    let mut start: Option<String> = None;
+    let mut analysis: Option<Analysis> = None;
+    let mut arg_iter = env::args();
+    while let Some(arg) = arg_iter.next() {
+        let mut value = || {
+            arg_iter
+                .next()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--stream" => stream_path = Some(value()?),
+            "--every" => every = parse_number("--every", &value()?)?,
+            "--start" => start = Some(value()?),
+            "--analyze" => analysis = Some(value()?.parse()?),
+            _ => args.push(arg),
+        }
+    }
+    // Starting numbers passed with --start take the place of the input
+    // file.
+    let (start_numbers, turns_arg) = match start {
+        Some(start) => (parse_start(&start)?, args.get(1)),
+        None => {
+            let filename = args.get(1).ok_or("missing input file")?;
+            let contents = fs::read_to_string(filename)
+                .map_err(|err| format!("couldn't read {}: {}", filename, err))?;
+            (parse_start(&contents)?, args.get(2))
+        }
+    };
+    let turns = match turns_arg {
+        Some(s) => parse_number("turns", s)?,
+        None => default_turns,
+    };
+    if turns == 0 || every == 0 {
+        return Err("turns and --every must be at least 1".to_string());
+    }
+    // An analysis writes its own table to stdout in place of the stream.
+    if analysis.is_some() && stream_path.is_some() {
+        return Err("--analyze can't be combined with --stream".to_string());
+    }
+
+    let estimate = MemoryGame::memory_estimate(&start_numbers, turns);
+    eprintln!(
+        "{} turns need {:.1} MiB",
+        turns,
+        estimate as f64 / (1024.0 * 1024.0)
+    );
+    let game = MemoryGame::new(&start_numbers, turns)
+        .map_err(|err| format!("couldn't allocate the game: {}", err))?;
+    if let Some(analysis) = analysis {
+        let mut out = BufWriter::new(io::stdout().lock());
+        analysis::analyze(&mut out, game, analysis, every)
+            .and_then(|_| out.flush())
+            .map_err(|err| format!("couldn't write the analysis: {}", err))?;
+        return Ok(());
+    }
+    // Writes every `every`th turn as a `turn,number` line, along with the
+    // final turn.
+    let write_error = |err: io::Error| format!("couldn't write the stream: {}", err);
+    let mut stream = match stream_path {
+        Some(path) => {
+            let file =
+                File::create(&path).map_err(|err| format!("couldn't create {}: {}", path, err))?;
+            let mut stream = BufWriter::new(file);
+            writeln!(stream, "turn,number").map_err(write_error)?;
+            Some(stream)
+        }
+        None => None,
+    };
+    let mut last = 0;
+    for (i, number) in game.enumerate() {
+        let turn = i as u32 + 1;
+        if let Some(stream) = stream.as_mut() {
+            if turn.is_multiple_of(every) || turn == turns {
+                writeln!(stream, "{},{}", turn, number).map_err(write_error)?;
+            }
+        }
+        last = number;
+    }
+    if let Some(mut stream) = stream {
+        stream.flush().map_err(write_error)?;
+    }
+    println!("{}", last);
+    return Ok(());
+}
+
+fn parse_start(s: &str) -> Result<Vec<u32>, String> {
+    return parse_start_numbers(s).map_err(|err| format!("invalid starting numbers: {}", err));
+}
+
+fn parse_number(name: &str, s: &str) -> Result<u32, String> {
+    return s
+        .parse()
+        .map_err(|_| format!("{} needs a number, not {}", name, s));
+}
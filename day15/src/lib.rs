@@ -0,0 +1,170 @@
+#![allow(clippy::needless_return)]
+
+use std::collections::TryReserveError;
+use std::mem;
+
+pub mod analysis;
+pub mod cli;
+
+// Marks a number that hasn't been spoken yet in `MemoryGame::last_spoken`.
+const UNSEEN: u32 = u32::MAX;
+
+/// Plays the memory game, yielding the number spoken on each turn starting
+/// with the starting numbers.
+///
+/// The turn on which each number was last spoken is kept in a `Vec` indexed
+/// by the number. Every number spoken after the starting numbers is an age,
+/// which is less than the turn it's spoken on, so a game of `turns` turns
+/// needs an entry for each number below `turns` (or the largest starting
+/// number, if that's bigger).
+pub struct MemoryGame {
+    start_numbers: Vec<u32>,
+    last_spoken: Vec<u32>,
+    // The turn that was last played, starting from 1.
+    turn: u32,
+    // The number spoken on `turn`.
+    current: u32,
+    turns: u32,
+}
+
+impl MemoryGame {
+    /// Creates a game of `turns` turns, failing if its table can't be
+    /// allocated.
+    pub fn new(start_numbers: &[u32], turns: u32) -> Result<MemoryGame, TryReserveError> {
+        assert!(turns < UNSEEN, "too many turns");
+        let mut last_spoken = Vec::new();
+        last_spoken.try_reserve_exact(MemoryGame::table_len(start_numbers, turns))?;
+        last_spoken.resize(last_spoken.capacity(), UNSEEN);
+        return Ok(MemoryGame {
+            start_numbers: start_numbers.to_vec(),
+            last_spoken,
+            turn: 0,
+            current: 0,
+            turns,
+        });
+    }
+
+    /// Returns the number of bytes a game of `turns` turns needs.
+    pub fn memory_estimate(start_numbers: &[u32], turns: u32) -> usize {
+        return MemoryGame::table_len(start_numbers, turns) * mem::size_of::<u32>();
+    }
+
+    /// The number of turns the game lasts.
+    pub fn turns(&self) -> u32 {
+        return self.turns;
+    }
+
+    /// Returns the turn on which `number` was spoken before the latest turn,
+    /// or None if it hadn't been spoken before then.
+    pub fn previous_turn(&self, number: u32) -> Option<u32> {
+        return match self.last_spoken.get(number as usize) {
+            Some(&UNSEEN) | None => None,
+            Some(&turn) => Some(turn),
+        };
+    }
+
+    fn table_len(start_numbers: &[u32], turns: u32) -> usize {
+        let largest_start = start_numbers.iter().max().map_or(0, |&n| n as usize + 1);
+        return largest_start.max(turns as usize);
+    }
+}
+
+impl Iterator for MemoryGame {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.turn == self.turns {
+            return None;
+        }
+        let next = match self.start_numbers.get(self.turn as usize) {
+            Some(&number) => number,
+            None => match self.last_spoken[self.current as usize] {
+                UNSEEN => 0,
+                last => self.turn - last,
+            },
+        };
+        if self.turn > 0 {
+            self.last_spoken[self.current as usize] = self.turn;
+        }
+        self.turn += 1;
+        self.current = next;
+        return Some(next);
+    }
+}
+
+/// Parses a comma-separated list of starting numbers.
+pub fn parse_start_numbers(s: &str) -> Result<Vec<u32>, std::num::ParseIntError> {
+    return s.trim().split(',').map(|s| s.trim().parse::<u32>()).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn play(start_numbers: &[u32], turns: u32) -> Vec<u32> {
+        return MemoryGame::new(start_numbers, turns).unwrap().collect();
+    }
+
+    // The game played with a map from number to the turn it was last
+    // spoken.
+    fn play_with_map(start_numbers: &[u32], turns: u32) -> Vec<u32> {
+        let mut spoken: Vec<u32> = Vec::new();
+        let mut last_spoken: HashMap<u32, u32> = HashMap::new();
+        for turn in 1..=turns {
+            let number = match start_numbers.get(turn as usize - 1) {
+                Some(&number) => number,
+                None => {
+                    let previous = *spoken.last().unwrap();
+                    last_spoken
+                        .get(&previous)
+                        .map_or(0, |&last| turn - 1 - last)
+                }
+            };
+            if let Some(&previous) = spoken.last() {
+                let _ = last_spoken.insert(previous, turn - 1);
+            }
+            spoken.push(number);
+        }
+        return spoken;
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(play(&[0, 3, 6], 10), vec![0, 3, 6, 0, 3, 3, 1, 0, 4, 0]);
+        let examples: [(&[u32], u32); 7] = [
+            (&[0, 3, 6], 436),
+            (&[1, 3, 2], 1),
+            (&[2, 1, 3], 10),
+            (&[1, 2, 3], 27),
+            (&[2, 3, 1], 78),
+            (&[3, 2, 1], 438),
+            (&[3, 1, 2], 1836),
+        ];
+        for (start_numbers, expected) in examples.iter() {
+            assert_eq!(play(start_numbers, 2020).last(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn repeated_start_numbers() {
+        assert_eq!(play(&[0, 0], 6), vec![0, 0, 1, 0, 2, 0]);
+        for start_numbers in &[vec![1, 1], vec![3, 0, 3], vec![5, 2, 5, 5, 0]] {
+            assert_eq!(play(start_numbers, 500), play_with_map(start_numbers, 500));
+        }
+    }
+
+    #[test]
+    fn fewer_turns_than_start_numbers() {
+        assert_eq!(play(&[9, 3, 6, 0], 2), vec![9, 3]);
+        assert_eq!(play(&[9, 3, 6, 0], 0), vec![]);
+        // The table still has room for the largest starting number.
+        assert_eq!(MemoryGame::memory_estimate(&[9, 3], 2), 10 * 4);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_start_numbers(" 0, 3,6\n"), Ok(vec![0, 3, 6]));
+        assert!(parse_start_numbers("0,,6").is_err());
+    }
+}
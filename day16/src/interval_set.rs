@@ -0,0 +1,208 @@
+use std::fmt;
+
+/// An inclusive range of values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.start, self.end) {
+            (start, end) if start == end => write!(f, "{}", start),
+            (start, u32::MAX) => write!(f, "{}-", start),
+            (start, end) => write!(f, "{}-{}", start, end),
+        }
+    }
+}
+
+/// A set of values stored as sorted, non-overlapping, non-adjacent ranges,
+/// so membership can be checked with a binary search.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IntervalSet {
+    ranges: Vec<Range>,
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        return IntervalSet { ranges: Vec::new() };
+    }
+
+    /// Builds a set from ranges in any order, merging any that overlap or
+    /// touch. Empty ranges (`start > end`) are ignored.
+    pub fn from_ranges<I: IntoIterator<Item = Range>>(ranges: I) -> IntervalSet {
+        let mut sorted: Vec<Range> = ranges.into_iter().filter(|r| r.start <= r.end).collect();
+        sorted.sort_by_key(|r| r.start);
+        let mut merged: Vec<Range> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
+            }
+        }
+        return IntervalSet { ranges: merged };
+    }
+
+    pub fn ranges(&self) -> &[Range] {
+        return &self.ranges;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.ranges.is_empty();
+    }
+
+    pub fn contains(&self, value: u32) -> bool {
+        // Index of the first range that ends at or after `value`.
+        let i = self.ranges.partition_point(|r| r.end < value);
+        return self.ranges.get(i).is_some_and(|r| r.start <= value);
+    }
+
+    /// Returns the range closest to `value` and its distance from it, or
+    /// None if the set is empty. A range containing `value` has distance 0.
+    pub fn nearest(&self, value: u32) -> Option<(Range, u32)> {
+        let i = self.ranges.partition_point(|r| r.end < value);
+        let after = self
+            .ranges
+            .get(i)
+            .map(|&r| (r, r.start.saturating_sub(value)));
+        let before = match i {
+            0 => None,
+            _ => Some((self.ranges[i - 1], value - self.ranges[i - 1].end)),
+        };
+        return match (before, after) {
+            (Some(b), Some(a)) if b.1 <= a.1 => Some(b),
+            (b, None) => b,
+            (_, a) => a,
+        };
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        return IntervalSet::from_ranges(self.ranges.iter().chain(other.ranges.iter()).cloned());
+    }
+
+    /// Returns the values in `self` that aren't in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let mut excluded = other.ranges.iter().peekable();
+        for range in &self.ranges {
+            let mut start = range.start;
+            let mut remaining = true;
+            while let Some(ex) = excluded.peek() {
+                if ex.end < start {
+                    let _ = excluded.next();
+                    continue;
+                }
+                if ex.start > range.end {
+                    break;
+                }
+                if ex.start > start {
+                    ranges.push(Range {
+                        start,
+                        end: ex.start - 1,
+                    });
+                }
+                if ex.end >= range.end {
+                    remaining = false;
+                    break;
+                }
+                start = ex.end + 1;
+                let _ = excluded.next();
+            }
+            if remaining {
+                ranges.push(Range {
+                    start,
+                    end: range.end,
+                });
+            }
+        }
+        return IntervalSet { ranges };
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranges: Vec<String> = self.ranges.iter().map(|r| r.to_string()).collect();
+        write!(f, "{}", ranges.join(" or "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(u32, u32)]) -> IntervalSet {
+        return IntervalSet::from_ranges(ranges.iter().map(|&(start, end)| Range { start, end }));
+    }
+
+    #[test]
+    fn from_ranges_merges_overlapping_and_adjacent() {
+        assert_eq!(
+            set(&[(10, 12), (1, 3), (4, 5), (2, 2), (7, 6)]),
+            set(&[(1, 5), (10, 12)])
+        );
+        assert_eq!(set(&[(1, 5), (10, 12)]).ranges().len(), 2);
+        assert_eq!(
+            set(&[(5, u32::MAX), (0, 4)]).ranges(),
+            &[Range {
+                start: 0,
+                end: u32::MAX
+            }]
+        );
+    }
+
+    #[test]
+    fn contains_and_nearest() {
+        let s = set(&[(1, 3), (10, 12)]);
+        assert!(s.contains(1) && s.contains(12));
+        assert!(!s.contains(0) && !s.contains(5) && !s.contains(13));
+        assert_eq!(s.nearest(5), Some((Range { start: 1, end: 3 }, 2)));
+        assert_eq!(s.nearest(8), Some((Range { start: 10, end: 12 }, 2)));
+        assert_eq!(IntervalSet::new().nearest(5), None);
+    }
+
+    #[test]
+    fn difference_with_nothing_to_remove() {
+        let s = set(&[(1, 3), (10, 12)]);
+        assert_eq!(s.difference(&IntervalSet::new()), s);
+        assert_eq!(s.difference(&set(&[(5, 8), (20, 30)])), s);
+        assert_eq!(IntervalSet::new().difference(&s), IntervalSet::new());
+    }
+
+    #[test]
+    fn difference_removing_everything() {
+        let s = set(&[(1, 3), (10, 12)]);
+        assert!(s.difference(&s).is_empty());
+        assert!(s.difference(&set(&[(0, u32::MAX)])).is_empty());
+    }
+
+    #[test]
+    fn difference_trims_ends_and_splits() {
+        let s = set(&[(10, 20)]);
+        assert_eq!(s.difference(&set(&[(5, 10)])), set(&[(11, 20)]));
+        assert_eq!(s.difference(&set(&[(20, 25)])), set(&[(10, 19)]));
+        assert_eq!(s.difference(&set(&[(15, 15)])), set(&[(10, 14), (16, 20)]));
+        assert_eq!(
+            s.difference(&set(&[(11, 12), (14, 15), (19, 19)])),
+            set(&[(10, 10), (13, 13), (16, 18), (20, 20)])
+        );
+    }
+
+    #[test]
+    fn difference_spanning_several_ranges() {
+        let s = set(&[(0, 2), (5, 7), (10, 12)]);
+        assert_eq!(s.difference(&set(&[(1, 11)])), set(&[(0, 0), (12, 12)]));
+    }
+
+    #[test]
+    fn difference_at_the_limits() {
+        let s = set(&[(0, u32::MAX)]);
+        assert_eq!(s.difference(&set(&[(0, 0)])), set(&[(1, u32::MAX)]));
+        assert_eq!(
+            s.difference(&set(&[(u32::MAX, u32::MAX)])),
+            set(&[(0, u32::MAX - 1)])
+        );
+    }
+}
@@ -0,0 +1,206 @@
+#![allow(clippy::needless_return)]
+
+use std::fmt;
+use std::io::BufRead;
+
+pub mod interval_set;
+pub mod validation;
+
+pub use interval_set::{IntervalSet, Range};
+
+/// The contents of a puzzle input.
+pub struct Notes {
+    pub rules: Vec<Rule>,
+    pub your_ticket: Vec<u32>,
+    /// Each nearby ticket with the (1-based) line number it was read from.
+    pub nearby_tickets: Vec<(usize, Vec<u32>)>,
+}
+
+impl Notes {
+    /// The number of fields every ticket should have: the length of your
+    /// ticket, or the number of rules if your ticket is missing.
+    pub fn num_fields(&self) -> usize {
+        return match self.your_ticket.len() {
+            0 => self.rules.len(),
+            n => n,
+        };
+    }
+}
+
+enum ParseStage {
+    Rules,
+    YourTicket,
+    NearbyTickets,
+}
+
+pub fn parse_notes<R: BufRead>(reader: R) -> Result<Notes, ParseError> {
+    let mut stage = ParseStage::Rules;
+    let mut notes = Notes {
+        rules: Vec::new(),
+        your_ticket: Vec::new(),
+        nearby_tickets: Vec::new(),
+    };
+    for (i, line) in reader.lines().map(|line| line.unwrap()).enumerate() {
+        match line.as_str() {
+            "" => {}
+            "your ticket:" => stage = ParseStage::YourTicket,
+            "nearby tickets:" => stage = ParseStage::NearbyTickets,
+            l => match &stage {
+                ParseStage::Rules => notes.rules.push(parse_rule(l)?),
+                ParseStage::YourTicket => notes.your_ticket = parse_ticket(l)?,
+                ParseStage::NearbyTickets => notes.nearby_tickets.push((i + 1, parse_ticket(l)?)),
+            },
+        }
+    }
+    return Ok(notes);
+}
+
+pub struct Rule {
+    pub field: String,
+    pub values: IntervalSet,
+}
+
+impl Rule {
+    pub fn validate(&self, value: u32) -> bool {
+        return self.values.contains(value);
+    }
+}
+
+/// Parses a rule of the form `<field>: <values> [except <values>]`, where
+/// `<values>` is a list of clauses separated by `or` or commas. Each clause
+/// is a range (`25-80`), a single value (`7`), or an open-ended range
+/// (`100-` for at least 100, `-30` for at most 30). For example:
+///
+/// `departure track: 1-20 or 40- except 45, 50-55`
+pub fn parse_rule(line: &str) -> Result<Rule, ParseError> {
+    let invalid = || ParseError::InvalidRule(line.to_string());
+    let mut components = line.splitn(2, ':');
+    let field = components.next().ok_or_else(invalid)?.trim();
+    let body = components.next().ok_or_else(invalid)?;
+    if field.is_empty() {
+        return Err(invalid());
+    }
+    let mut parts = body.splitn(2, " except ");
+    let included = parse_values(parts.next().unwrap()).ok_or_else(invalid)?;
+    let values = match parts.next() {
+        Some(excluded) => included.difference(&parse_values(excluded).ok_or_else(invalid)?),
+        None => included,
+    };
+    return Ok(Rule {
+        field: field.to_string(),
+        values,
+    });
+}
+
+fn parse_values(s: &str) -> Option<IntervalSet> {
+    let ranges: Option<Vec<Range>> = s
+        .split(',')
+        .flat_map(|part| part.split(" or "))
+        .map(|clause| parse_clause(clause.trim()))
+        .collect();
+    return Some(IntervalSet::from_ranges(ranges?));
+}
+
+fn parse_clause(clause: &str) -> Option<Range> {
+    let parse_bound = |s: &str, default: u32| -> Option<u32> {
+        match s.trim() {
+            "" => Some(default),
+            bound => bound.parse::<u32>().ok(),
+        }
+    };
+    if clause.is_empty() {
+        return None;
+    }
+    return match clause.find('-') {
+        Some(i) if clause.len() > 1 => {
+            let start = parse_bound(&clause[..i], 0)?;
+            let end = parse_bound(&clause[i + 1..], u32::MAX)?;
+            // A reversed range is a mistake rather than an empty clause.
+            if start > end {
+                return None;
+            }
+            Some(Range { start, end })
+        }
+        Some(_) => None,
+        None => {
+            let value = clause.parse::<u32>().ok()?;
+            Some(Range {
+                start: value,
+                end: value,
+            })
+        }
+    };
+}
+
+pub fn parse_ticket(line: &str) -> Result<Vec<u32>, ParseError> {
+    return line
+        .split(',')
+        .map(|s| {
+            s.trim()
+                .parse::<u32>()
+                .map_err(|_| ParseError::InvalidTicket(line.to_string()))
+        })
+        .collect();
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    InvalidRule(String),
+    InvalidTicket(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::InvalidRule(s) => write!(f, "invalid rule: {}", s),
+            ParseError::InvalidTicket(s) => write!(f, "invalid ticket: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(rule: &str) -> String {
+        return parse_rule(rule).unwrap().values.to_string();
+    }
+
+    #[test]
+    fn parse_puzzle_rules() {
+        let rule = parse_rule("departure location: 1-3 or 5-7").unwrap();
+        assert_eq!(rule.field, "departure location");
+        assert!(rule.validate(3) && rule.validate(5) && !rule.validate(4));
+    }
+
+    #[test]
+    fn parse_extended_rules() {
+        assert_eq!(values("a: 7, 1-3 or 4-5"), "1-5 or 7");
+        assert_eq!(values("b: -30, 100-"), "0-30 or 100-");
+        assert_eq!(
+            values("c: 1-20 or 40- except 45, 50-55"),
+            "1-20 or 40-44 or 46-49 or 56-"
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        for rule in &[
+            "class 1-3",
+            ": 1-3",
+            "class: ",
+            "class: 1-3 or",
+            "class: 5-3",
+            "class: 1-9 except 6-4",
+            "class: x",
+        ] {
+            assert!(
+                matches!(parse_rule(rule), Err(ParseError::InvalidRule(_))),
+                "{}",
+                rule
+            );
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{Notes, Range, Rule};
+
+/// Why a nearby ticket was rejected.
+pub enum TicketProblem {
+    /// The ticket has a different number of fields than your ticket.
+    WrongFieldCount { expected: usize, found: usize },
+    /// Some values don't satisfy any rule.
+    InvalidValues(Vec<InvalidValue>),
+}
+
+/// A ticket value that doesn't satisfy any rule.
+pub struct InvalidValue {
+    /// Index of the value within the ticket.
+    pub position: usize,
+    pub value: u32,
+    /// The rule ranges closest to the value, all at the same distance from
+    /// it, as (field, range, distance) tuples.
+    pub nearest: Vec<(String, Range, u32)>,
+}
+
+/// Returns the values in `ticket` that don't satisfy any rule.
+pub fn invalid_values(ticket: &[u32], rules: &[Rule]) -> Vec<InvalidValue> {
+    return ticket
+        .iter()
+        .enumerate()
+        .filter(|&(_, &value)| !rules.iter().any(|rule| rule.validate(value)))
+        .map(|(position, &value)| {
+            let candidates: Vec<(String, Range, u32)> = rules
+                .iter()
+                .filter_map(|rule| {
+                    let (range, distance) = rule.values.nearest(value)?;
+                    Some((rule.field.clone(), range, distance))
+                })
+                .collect();
+            let min_distance = candidates.iter().map(|c| c.2).min();
+            InvalidValue {
+                position,
+                value,
+                nearest: candidates
+                    .into_iter()
+                    .filter(|c| Some(c.2) == min_distance)
+                    .collect(),
+            }
+        })
+        .collect();
+}
+
+/// Checks a nearby ticket against the rules, returning None if it's valid.
+pub fn validate_ticket(ticket: &[u32], rules: &[Rule], num_fields: usize) -> Option<TicketProblem> {
+    if ticket.len() != num_fields {
+        return Some(TicketProblem::WrongFieldCount {
+            expected: num_fields,
+            found: ticket.len(),
+        });
+    }
+    let invalid = invalid_values(ticket, rules);
+    if invalid.is_empty() {
+        return None;
+    }
+    return Some(TicketProblem::InvalidValues(invalid));
+}
+
+/// Describes a rejected ticket read from `line`, e.g.
+///
+/// ```text
+/// line 10: 40,4,50
+///   value 4 at position 2 matches no field; nearest: class 1-3 (off by 1), class 5-7 (off by 1)
+/// ```
+pub fn describe_rejection(line: usize, ticket: &[u32], problem: &TicketProblem) -> String {
+    let values: Vec<String> = ticket.iter().map(|v| v.to_string()).collect();
+    let mut description = format!("line {}: {}", line, values.join(","));
+    match problem {
+        TicketProblem::WrongFieldCount { expected, found } => {
+            description.push_str(&format!("\n  has {} fields, expected {}", found, expected));
+        }
+        TicketProblem::InvalidValues(invalid) => {
+            for value in invalid {
+                let nearest: Vec<String> = value
+                    .nearest
+                    .iter()
+                    .map(|(field, range, distance)| {
+                        format!("{} {} (off by {})", field, range, distance)
+                    })
+                    .collect();
+                description.push_str(&format!(
+                    "\n  value {} at position {} matches no field; nearest: {}",
+                    value.value,
+                    value.position + 1,
+                    nearest.join(", ")
+                ));
+            }
+        }
+    }
+    return description;
+}
+
+/// Describes every rejected nearby ticket, followed by a summary line.
+pub fn report(notes: &Notes) -> String {
+    let num_fields = notes.num_fields();
+    let rejections: Vec<String> = notes
+        .nearby_tickets
+        .iter()
+        .filter_map(|(line, ticket)| {
+            let problem = validate_ticket(ticket, &notes.rules, num_fields)?;
+            Some(describe_rejection(*line, ticket, &problem))
+        })
+        .collect();
+    let mut output = String::new();
+    for rejection in &rejections {
+        output.push_str(rejection);
+        output.push('\n');
+    }
+    output.push_str(&format!(
+        "{} of {} nearby tickets rejected\n",
+        rejections.len(),
+        notes.nearby_tickets.len()
+    ));
+    return output;
+}
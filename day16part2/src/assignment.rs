@@ -0,0 +1,172 @@
+// Assigns ticket columns to rule fields as a bipartite matching problem:
+// column i may be matched to rule j if every value in column i satisfies
+// rule j, and every column must be matched to a different rule.
+
+pub enum Assignment {
+    // The only valid assignment, indexed by column.
+    Unique(Vec<usize>),
+    // Every valid assignment (up to the enumeration limit), and whether the
+    // limit was reached before all of them were found.
+    Ambiguous(Vec<Vec<usize>>, bool),
+    // No valid assignment exists. Holds a set of columns that together only
+    // have candidates among fewer rules than there are columns.
+    Conflict(Vec<usize>, Vec<usize>),
+}
+
+// `candidates[i]` lists the rules that column i may be assigned to. At most
+// `limit` assignments are enumerated when the answer is ambiguous.
+pub fn assign_fields(candidates: &[Vec<usize>], num_rules: usize, limit: usize) -> Assignment {
+    if let Some((columns, rules)) = find_conflict(candidates, num_rules) {
+        return Assignment::Conflict(columns, rules);
+    }
+    let mut solutions = Vec::new();
+    let mut assigned = vec![None; candidates.len()];
+    let mut claimed = vec![false; num_rules];
+    let truncated = !enumerate(
+        candidates,
+        &mut assigned,
+        &mut claimed,
+        &mut solutions,
+        limit,
+    );
+    return match solutions.len() {
+        1 => Assignment::Unique(solutions.remove(0)),
+        _ => Assignment::Ambiguous(solutions, truncated),
+    };
+}
+
+// Depth-first search that always branches on the unassigned column with the
+// fewest remaining candidates, so forced assignments are propagated before
+// any real choice is made. Returns false once `limit` solutions are found.
+fn enumerate(
+    candidates: &[Vec<usize>],
+    assigned: &mut [Option<usize>],
+    claimed: &mut [bool],
+    solutions: &mut Vec<Vec<usize>>,
+    limit: usize,
+) -> bool {
+    let next = (0..candidates.len())
+        .filter(|&i| assigned[i].is_none())
+        .map(|i| {
+            let available: Vec<usize> = candidates[i]
+                .iter()
+                .cloned()
+                .filter(|&rule| !claimed[rule])
+                .collect();
+            (i, available)
+        })
+        .min_by_key(|(_, available)| available.len());
+    let (column, available) = match next {
+        Some(next) => next,
+        None => {
+            solutions.push(assigned.iter().map(|rule| rule.unwrap()).collect());
+            return solutions.len() < limit;
+        }
+    };
+    for rule in available {
+        assigned[column] = Some(rule);
+        claimed[rule] = true;
+        let keep_going = enumerate(candidates, assigned, claimed, solutions, limit);
+        assigned[column] = None;
+        claimed[rule] = false;
+        if !keep_going {
+            return false;
+        }
+    }
+    return true;
+}
+
+// Finds a maximum matching with augmenting paths (Kuhn's algorithm). If some
+// column is left unmatched, the columns reachable from it along alternating
+// paths have fewer candidate rules than columns between them (Hall's
+// theorem), which is returned as the conflict.
+fn find_conflict(candidates: &[Vec<usize>], num_rules: usize) -> Option<(Vec<usize>, Vec<usize>)> {
+    let mut rule_owner: Vec<Option<usize>> = vec![None; num_rules];
+    for column in 0..candidates.len() {
+        let mut visited = vec![false; num_rules];
+        if augment(column, candidates, &mut rule_owner, &mut visited) {
+            continue;
+        }
+        // `visited` now marks exactly the rules reachable from `column`, all
+        // of which are matched to other reachable columns.
+        let rules: Vec<usize> = (0..num_rules).filter(|&rule| visited[rule]).collect();
+        let mut columns: Vec<usize> = rules
+            .iter()
+            .map(|&rule| rule_owner[rule].unwrap())
+            .collect();
+        columns.push(column);
+        columns.sort_unstable();
+        return Some((columns, rules));
+    }
+    return None;
+}
+
+fn augment(
+    column: usize,
+    candidates: &[Vec<usize>],
+    rule_owner: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for &rule in &candidates[column] {
+        if visited[rule] {
+            continue;
+        }
+        visited[rule] = true;
+        let available = match rule_owner[rule] {
+            None => true,
+            Some(owner) => augment(owner, candidates, rule_owner, visited),
+        };
+        if available {
+            rule_owner[rule] = Some(column);
+            return true;
+        }
+    }
+    return false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_assignment() {
+        // The example from part 2: the columns are row, class and seat.
+        let candidates = vec![vec![1], vec![0, 1], vec![0, 1, 2]];
+        assert!(matches!(
+            assign_fields(&candidates, 3, 10),
+            Assignment::Unique(rules) if rules == vec![1, 0, 2]
+        ));
+    }
+
+    #[test]
+    fn ambiguous_assignment() {
+        let candidates = vec![vec![0, 1], vec![0, 1]];
+        match assign_fields(&candidates, 2, 10) {
+            Assignment::Ambiguous(mut solutions, truncated) => {
+                solutions.sort();
+                assert_eq!(solutions, vec![vec![0, 1], vec![1, 0]]);
+                assert!(!truncated);
+            }
+            _ => panic!("expected an ambiguous assignment"),
+        }
+    }
+
+    #[test]
+    fn enumeration_stops_at_the_limit() {
+        let candidates = vec![vec![0, 1, 2]; 3];
+        assert!(matches!(
+            assign_fields(&candidates, 3, 4),
+            Assignment::Ambiguous(solutions, true) if solutions.len() == 4
+        ));
+    }
+
+    #[test]
+    fn conflict() {
+        // Columns 0, 1 and 3 only fit rules 0 and 1.
+        let candidates = vec![vec![0, 1], vec![0], vec![0, 1, 2, 3], vec![1]];
+        assert!(matches!(
+            assign_fields(&candidates, 4, 10),
+            Assignment::Conflict(columns, rules) if columns == vec![0, 1, 3] && rules == vec![0, 1]
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+// Writes decoded tickets as records keyed by their resolved field names.
+
+use std::io::{self, Write};
+use std::str::FromStr;
+
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        return match s {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown export format: {}", s)),
+        };
+    }
+}
+
+// `fields[i]` is the name of the field in column i of every ticket. Every
+// ticket must have one value per field. `out` is flushed once everything
+// has been written.
+pub fn export<W: Write>(
+    out: &mut W,
+    format: Format,
+    fields: &[&str],
+    your_ticket: &[u32],
+    nearby_tickets: &[&Vec<u32>],
+) -> io::Result<()> {
+    let tickets = std::iter::once(your_ticket).chain(nearby_tickets.iter().map(|t| t.as_slice()));
+    for ticket in tickets {
+        if ticket.len() != fields.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "a ticket has {} values but there are {} fields",
+                    ticket.len(),
+                    fields.len()
+                ),
+            ));
+        }
+    }
+    match format {
+        Format::Csv => write_csv(out, fields, your_ticket, nearby_tickets)?,
+        Format::Json => write_json(out, fields, your_ticket, nearby_tickets)?,
+    }
+    return out.flush();
+}
+
+// One row per ticket, with a leading `source` column that is either
+// "your ticket" or "nearby ticket".
+fn write_csv<W: Write>(
+    out: &mut W,
+    fields: &[&str],
+    your_ticket: &[u32],
+    nearby_tickets: &[&Vec<u32>],
+) -> io::Result<()> {
+    let header: Vec<String> = fields.iter().map(|field| csv_escape(field)).collect();
+    writeln!(out, "source,{}", header.join(","))?;
+    let tickets = std::iter::once(("your ticket", your_ticket)).chain(
+        nearby_tickets
+            .iter()
+            .map(|t| ("nearby ticket", t.as_slice())),
+    );
+    for (source, ticket) in tickets {
+        let values: Vec<String> = ticket.iter().map(|v| v.to_string()).collect();
+        writeln!(out, "{},{}", source, values.join(","))?;
+    }
+    return Ok(());
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", s.replace('"', "\"\""));
+    }
+    return s.to_string();
+}
+
+// An object with `your_ticket` holding a single record and `nearby_tickets`
+// holding an array of records.
+fn write_json<W: Write>(
+    out: &mut W,
+    fields: &[&str],
+    your_ticket: &[u32],
+    nearby_tickets: &[&Vec<u32>],
+) -> io::Result<()> {
+    writeln!(out, "{{")?;
+    writeln!(
+        out,
+        "  \"your_ticket\": {},",
+        json_record(fields, your_ticket)
+    )?;
+    writeln!(out, "  \"nearby_tickets\": [")?;
+    for (i, ticket) in nearby_tickets.iter().enumerate() {
+        let separator = if i + 1 < nearby_tickets.len() {
+            ","
+        } else {
+            ""
+        };
+        writeln!(out, "    {}{}", json_record(fields, ticket), separator)?;
+    }
+    writeln!(out, "  ]")?;
+    writeln!(out, "}}")?;
+    return Ok(());
+}
+
+fn json_record(fields: &[&str], ticket: &[u32]) -> String {
+    let members: Vec<String> = fields
+        .iter()
+        .zip(ticket)
+        .map(|(field, value)| format!("{}: {}", json_string(field), value))
+        .collect();
+    return format!("{{{}}}", members.join(", "));
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    return escaped;
+}
@@ -1,157 +1,159 @@
+#![allow(clippy::needless_return)]
+
 use std::collections::HashSet;
 use std::env;
 use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::{self, BufWriter};
+use std::process;
 
-#[macro_use]
-extern crate lazy_static;
-extern crate regex;
+use day16::{parse_notes, validation};
 
-use regex::Regex;
+mod assignment;
+mod export;
 
-enum ParseStage {
-    Rules,
-    YourTicket,
-    NearbyTickets,
-}
+use assignment::Assignment;
+use export::Format;
 
+// usage: day16part2 <path to input text file> [--report | --export csv|json [output path]]
 fn main() {
     let args: Vec<String> = env::args().collect();
     let filename = &args[1];
     let file = File::open(filename).unwrap();
 
-    let mut stage = ParseStage::Rules;
-    let mut rules = Vec::new();
-    let mut your_ticket = Vec::new();
-    let mut nearby_tickets = Vec::new();
-    io::BufReader::new(file)
-        .lines()
-        .map(|line| line.unwrap())
-        .for_each(|line| match line.as_str() {
-            "" => {}
-            "your ticket:" => stage = ParseStage::YourTicket,
-            "nearby tickets:" => stage = ParseStage::NearbyTickets,
-            l => match &stage {
-                ParseStage::Rules => rules.push(parse_rule(&l).unwrap()),
-                ParseStage::YourTicket => your_ticket = parse_ticket(l),
-                ParseStage::NearbyTickets => nearby_tickets.push(parse_ticket(l)),
-            },
-        });
-    let num_fields = your_ticket.len();
-    let valid_tickets: Vec<Vec<u32>> = nearby_tickets
-        .into_iter()
-        .filter(|ticket| is_valid_ticket(ticket, &rules))
+    let notes = match parse_notes(io::BufReader::new(file)) {
+        Ok(notes) => notes,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+    if args.get(2).map(|s| s.as_str()) == Some("--report") {
+        print!("{}", validation::report(&notes));
+        return;
+    }
+    let export_format: Option<Format> = match args.get(2).map(|s| s.as_str()) {
+        Some("--export") => Some(args.get(3).expect("missing export format").parse().unwrap()),
+        _ => None,
+    };
+    let rules = &notes.rules;
+    let your_ticket = &notes.your_ticket;
+    let num_fields = notes.num_fields();
+    // Tickets with the wrong number of fields are rejected along with those
+    // that have invalid values, so every remaining ticket has every column.
+    let valid_tickets: Vec<&Vec<u32>> = notes
+        .nearby_tickets
+        .iter()
+        .map(|(_, ticket)| ticket)
+        .filter(|ticket| validation::validate_ticket(ticket, rules, num_fields).is_none())
         .collect();
-    let mut valid_rules: Vec<(usize, Vec<String>)> = (0..num_fields)
-        .map(|i| -> (usize, Vec<u32>) {
-            (i, valid_tickets.iter().map(|ticket| ticket[i]).collect())
-        })
-        .map(|(i, values)| -> (usize, Vec<String>) {
-            (
-                i,
-                rules
-                    .iter()
-                    .filter(|rule| {
-                        values
-                            .iter()
-                            .map(|&value| !rule.validate(value))
-                            .filter(|&x| x)
-                            .count()
-                            == 0
-                    })
-                    .map(|rule| rule.field.clone())
-                    .collect(),
-            )
+    let candidates: Vec<Vec<usize>> = (0..num_fields)
+        .map(|i| -> Vec<u32> { valid_tickets.iter().map(|ticket| ticket[i]).collect() })
+        .map(|values| -> Vec<usize> {
+            (0..rules.len())
+                .filter(|&j| values.iter().all(|&value| rules[j].validate(value)))
+                .collect()
         })
         .collect();
-    valid_rules.sort_by(|a, b| a.1.len().partial_cmp(&b.1.len()).unwrap());
 
-    let mut claimed_fields = HashSet::new();
-    let field_assignments: Vec<(usize, String)> = valid_rules
-        .iter()
-        .map(|(i, fields)| -> (usize, String) {
-            let available_fields: Vec<String> = fields
-                .clone()
-                .into_iter()
-                .filter(|field| !claimed_fields.contains(field))
+    // None if the notes don't have your ticket.
+    let departure_product = |assignment: &[usize]| -> Option<u64> {
+        if your_ticket.len() != assignment.len() {
+            return None;
+        }
+        Some(
+            assignment
+                .iter()
+                .enumerate()
+                .filter(|&(_, &j)| rules[j].field.starts_with("departure"))
+                .map(|(i, _)| your_ticket[i] as u64)
+                .product(),
+        )
+    };
+    let missing_ticket = || -> ! {
+        eprintln!("the notes don't include your ticket, so there's no departure product");
+        process::exit(1);
+    };
+    match assignment::assign_fields(&candidates, rules.len(), 100) {
+        Assignment::Unique(assignment) => match export_format {
+            Some(format) => {
+                let fields: Vec<&str> = assignment
+                    .iter()
+                    .map(|&j| rules[j].field.as_str())
+                    .collect();
+                let result = match args.get(4) {
+                    Some(path) => File::create(path).and_then(|file| {
+                        export::export(
+                            &mut BufWriter::new(file),
+                            format,
+                            &fields,
+                            your_ticket,
+                            &valid_tickets,
+                        )
+                    }),
+                    None => export::export(
+                        &mut io::stdout().lock(),
+                        format,
+                        &fields,
+                        your_ticket,
+                        &valid_tickets,
+                    ),
+                };
+                if let Err(err) = result {
+                    eprintln!("couldn't export the tickets: {}", err);
+                    process::exit(1);
+                }
+            }
+            None => match departure_product(&assignment) {
+                Some(product) => println!("{}", product),
+                None => missing_ticket(),
+            },
+        },
+        Assignment::Ambiguous(assignments, truncated) => {
+            eprintln!(
+                "{}{} valid field assignments:",
+                if truncated { "at least " } else { "" },
+                assignments.len()
+            );
+            for assignment in &assignments {
+                let fields: Vec<&str> = assignment
+                    .iter()
+                    .map(|&j| rules[j].field.as_str())
+                    .collect();
+                eprintln!("  {}", fields.join(", "));
+            }
+            // The answer only needs the departure fields, which may still be
+            // pinned down even if other fields are interchangeable.
+            let products: HashSet<Option<u64>> = assignments
+                .iter()
+                .map(|assignment| departure_product(assignment))
                 .collect();
-            (
-                i.clone(),
-                match available_fields.len() {
-                    1 => {
-                        let field = available_fields[0].clone();
-                        claimed_fields.insert(field.clone());
-                        field
-                    }
-                    _ => "<undefined>".to_string(),
-                },
-            )
-        })
-        .collect();
-
-    let product: u64 = field_assignments
-        .iter()
-        .filter(|(_, field)| field.starts_with("departure"))
-        .map(|&(i, _)| your_ticket[i] as u64)
-        .product();
-    println!("{}", product);
-}
-
-struct Range {
-    start: u32,
-    end: u32,
-}
-
-struct Rule {
-    field: String,
-    ranges: Vec<Range>,
-}
-
-impl Rule {
-    fn validate(&self, value: u32) -> bool {
-        for r in &self.ranges {
-            if value >= r.start && value <= r.end {
-                return true;
+            if export_format.is_some() {
+                eprintln!("tickets can't be exported without a unique field assignment");
+                process::exit(1);
+            }
+            if products.contains(&None) {
+                missing_ticket();
             }
+            if products.len() != 1 || truncated {
+                eprintln!("the departure fields are ambiguous");
+                process::exit(1);
+            }
+            println!("{}", products.into_iter().next().unwrap().unwrap());
+        }
+        Assignment::Conflict(columns, fields) => {
+            eprintln!(
+                "no valid field assignment: columns {:?} can only be {} field(s):",
+                columns,
+                fields.len()
+            );
+            for &i in &columns {
+                let fields: Vec<&str> = candidates[i]
+                    .iter()
+                    .map(|&j| rules[j].field.as_str())
+                    .collect();
+                eprintln!("  column {}: {}", i, fields.join(", "));
+            }
+            process::exit(1);
         }
-        return false;
-    }
-}
-
-fn parse_ticket(line: &str) -> Vec<u32> {
-    return line.split(",").map(|s| s.parse::<u32>().unwrap()).collect();
-}
-
-fn parse_rule(line: &str) -> Option<Rule> {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"([\w\s]+): (\d+)-(\d+) or (\d+)-(\d+)").unwrap();
     }
-    let captures = RE.captures(line)?;
-    return Some(Rule {
-        field: captures.get(1)?.as_str().to_string(),
-        ranges: vec![
-            Range {
-                start: captures.get(2)?.as_str().parse::<u32>().ok()?,
-                end: captures.get(3)?.as_str().parse::<u32>().ok()?,
-            },
-            Range {
-                start: captures.get(4)?.as_str().parse::<u32>().ok()?,
-                end: captures.get(5)?.as_str().parse::<u32>().ok()?,
-            },
-        ],
-    });
-}
-
-fn is_valid_ticket(ticket: &Vec<u32>, rules: &Vec<Rule>) -> bool {
-    return ticket
-        .iter()
-        .map(|&field| {
-            rules
-                .iter()
-                .map(|rule| rule.validate(field))
-                .filter(|&x| x)
-                .count()
-        })
-        .find(|&count| count == 0)
-        .is_none();
 }
@@ -0,0 +1,165 @@
+use crate::render;
+use crate::{ConwayCubeGrid, MAX_DIMS};
+use lifelike::{Neighborhood, Rule};
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+
+const USAGE: &str = "usage: <path to input text file> [dimensions] [cycles] [--mirror]
+    [--rule B3/S23] [--neighborhood moore|von-neumann]
+    [--render] [--slice z,w,...] [--png <directory>] [--gif <path>]
+    [--scale <pixels per cube>] [--delay <milliseconds per frame>]";
+
+/// Simulates the pocket dimension described by the input file in
+/// `default_dims` dimensions, or as many as the command line asks for, and
+/// prints how many cubes are left active. Bad arguments or input are
+/// reported along with the usage text, and the process exits with status 1.
+pub fn run(default_dims: usize) {
+    if let Err(err) = run_args(default_dims) {
+        eprintln!("{}", err);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run_args(default_dims: usize) -> Result<(), String> {
+    let mut args = Vec::new();
+    let mut mirror = false;
+    let mut rule: Rule = "B3/S23".parse().unwrap();
+    let mut neighborhood = Neighborhood::Moore;
+    let mut render_ascii = false;
+    let mut slice: Option<Vec<isize>> = None;
+    let mut png_dir: Option<String> = None;
+    let mut gif_path: Option<String> = None;
+    let mut scale = 8usize;
+    let mut delay_ms = 500u16;
+    let mut arg_iter = env::args();
+    while let Some(arg) = arg_iter.next() {
+        let mut value = || {
+            arg_iter
+                .next()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--mirror" => mirror = true,
+            "--rule" => rule = parse(&value()?)?,
+            "--neighborhood" => neighborhood = parse(&value()?)?,
+            "--render" => render_ascii = true,
+            "--slice" => {
+                slice = Some(
+                    value()?
+                        .split(',')
+                        .map(|s| parse_number("--slice", s))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            "--png" => png_dir = Some(value()?),
+            "--gif" => gif_path = Some(value()?),
+            "--scale" => {
+                scale = value()?
+                    .parse()
+                    .ok()
+                    .filter(|&scale| scale > 0)
+                    .ok_or("--scale needs a positive number of pixels")?
+            }
+            "--delay" => delay_ms = parse_number("--delay", &value()?)?,
+            _ => args.push(arg),
+        }
+    }
+    let filename = args.get(1).ok_or("missing input file")?;
+    let file =
+        File::open(filename).map_err(|err| format!("couldn't open {}: {}", filename, err))?;
+    let dims = match args.get(2) {
+        Some(s) => parse_number("dimensions", s)?,
+        None => default_dims,
+    };
+    let cycles = match args.get(3) {
+        Some(s) => parse_number("cycles", s)?,
+        None => 6,
+    };
+    if !(2..=MAX_DIMS).contains(&dims) {
+        return Err(format!("the pocket needs 2 to {} dimensions", MAX_DIMS));
+    }
+    rule.check_counts(neighborhood, dims)
+        .map_err(|err| err.to_string())?;
+    if slice.as_ref().is_some_and(|slice| slice.len() != dims - 2) {
+        return Err(format!(
+            "--slice needs one coordinate for each of the {} axes past x and y",
+            dims - 2
+        ));
+    }
+    if rule.is_birth(0) {
+        return Err("B0 rules would activate every cube in the infinite pocket".to_string());
+    }
+
+    let mut grid =
+        ConwayCubeGrid::parse(io::BufReader::new(file), dims).with_rule(rule, neighborhood);
+    if mirror {
+        grid = grid.mirrored();
+    }
+    if cycles > grid.max_cycles() {
+        return Err(format!(
+            "can't run more than {} cycles in {} dimensions",
+            grid.max_cycles(),
+            dims
+        ));
+    }
+    let keep_generations = png_dir.is_some() || gif_path.is_some();
+    let mut generations = Vec::new();
+    for cycle in 0..=cycles {
+        if cycle > 0 {
+            grid = grid.step();
+        }
+        if render_ascii {
+            match cycle {
+                0 => println!("Before any cycles:\n"),
+                1 => println!("After 1 cycle:\n"),
+                n => println!("After {} cycles:\n", n),
+            }
+            print!("{}", render::render_slices(&grid));
+        }
+        if keep_generations {
+            generations.push(grid.clone());
+        }
+    }
+
+    if keep_generations {
+        let slice = slice.unwrap_or_else(|| vec![0; dims - 2]);
+        let frames = render::slice_frames(&generations, &slice);
+        if let Some(dir) = png_dir {
+            fs::create_dir_all(&dir).map_err(|err| format!("couldn't create {}: {}", dir, err))?;
+            for (cycle, frame) in frames.iter().enumerate() {
+                let path = Path::new(&dir).join(format!("cycle_{:03}.png", cycle));
+                render::write_png(&path, frame, scale)
+                    .map_err(|err| format!("couldn't write {}: {}", path.display(), err))?;
+            }
+        }
+        if let Some(path) = gif_path {
+            render::write_gif(Path::new(&path), &frames, scale, delay_ms)
+                .map_err(|err| format!("couldn't write {}: {}", path, err))?;
+        }
+    }
+
+    println!("{}", grid.len());
+    return Ok(());
+}
+
+fn parse<T>(s: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    return s.parse().map_err(|err: T::Err| err.to_string());
+}
+
+// Numbers get their own message, since the standard library's parse errors
+// don't say which argument was wrong.
+fn parse_number<T: FromStr>(name: &str, s: &str) -> Result<T, String> {
+    return s
+        .parse()
+        .map_err(|_| format!("{} needs a number, not {}", name, s));
+}
@@ -0,0 +1,526 @@
+#![allow(clippy::needless_return)]
+
+use std::convert::Infallible;
+use std::io::BufRead;
+
+use grid::Grid;
+use lifelike::{Neighborhood, Rule};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+pub mod cli;
+pub mod render;
+
+/// A cell position with one component per dimension.
+pub type Coordinate = Vec<isize>;
+
+/// The most dimensions a grid can have. Past that, a cube has over 43
+/// million neighbors, and stepping it isn't practical anyway.
+pub const MAX_DIMS: usize = 16;
+
+// Cubes are stored as a single u128 with a fixed number of bits per axis,
+// so that keys are cheap to hash and compare and never allocate. Each axis
+// is stored with a bias of half its range, so while every axis stays in
+// range, adding a packed offset moves all of them at once.
+type Key = u128;
+
+#[derive(Clone, Copy)]
+struct Packing {
+    dims: usize,
+    bits: u32,
+}
+
+impl Packing {
+    fn new(dims: usize) -> Packing {
+        return Packing {
+            dims,
+            bits: (128 / dims as u32).min(32),
+        };
+    }
+
+    // The largest distance from the origin along any axis that can be
+    // stored.
+    fn limit(&self) -> isize {
+        return (1 << (self.bits - 1)) - 1;
+    }
+
+    fn pack(&self, coord: &[isize]) -> Key {
+        let bias = 1i128 << (self.bits - 1);
+        return coord[..self.dims]
+            .iter()
+            .enumerate()
+            .fold(0, |key, (axis, &c)| {
+                key | (((c as i128 + bias) as Key) << (axis as u32 * self.bits))
+            });
+    }
+
+    // Packs an offset without the bias, to be added to a packed key.
+    fn pack_offset(&self, offset: &[isize]) -> Key {
+        return offset.iter().enumerate().fold(0, |key: Key, (axis, &o)| {
+            key.wrapping_add(((o as i128) << (axis as u32 * self.bits)) as Key)
+        });
+    }
+
+    fn unpack(&self, key: Key, coord: &mut [isize]) {
+        let bias = 1i128 << (self.bits - 1);
+        let mask = (1 << self.bits) - 1;
+        for (axis, c) in coord[..self.dims].iter_mut().enumerate() {
+            *c = (((key >> (axis as u32 * self.bits)) & mask) as i128 - bias) as isize;
+        }
+    }
+}
+
+/// Sparse set of active cubes in an N-dimensional Conway cube pocket.
+#[derive(Clone)]
+pub struct ConwayCubeGrid {
+    dims: usize,
+    packing: Packing,
+    active: FxHashSet<Key>,
+    // An upper bound on the distance of any active cube from the origin
+    // along any axis, which grows by at most 1 per step.
+    reach: isize,
+    // When set, only one cube is stored out of each set of cubes that are
+    // the same up to negating and reordering the coordinates past the first
+    // two: the one whose higher coordinates are non-negative and in
+    // ascending order. The pocket starts as a single slice at the origin of
+    // those axes, and every rule treats them alike, so it always has that
+    // symmetry.
+    mirrored: bool,
+    rule: Rule,
+    neighborhood: Neighborhood,
+}
+
+impl ConwayCubeGrid {
+    /// Parses the puzzle's 2D starting slice (`#` = active) and places it
+    /// at the origin of every dimension past the first two.
+    pub fn parse<R: BufRead>(reader: R, dims: usize) -> ConwayCubeGrid {
+        assert!(
+            (2..=MAX_DIMS).contains(&dims),
+            "a grid needs 2 to {} dimensions",
+            MAX_DIMS
+        );
+        // Short rows are padded with inactive cubes.
+        let slice = Grid::parse_padded(reader, false, |c| Ok::<bool, Infallible>(c == '#'))
+            .expect("failed to read the starting slice");
+        let packing = Packing::new(dims);
+        let reach = slice.width().max(slice.height()) as isize;
+        assert!(
+            reach <= packing.limit(),
+            "the starting slice is too large for {} dimensions",
+            dims
+        );
+        let active = slice
+            .iter()
+            .filter(|&(_, &active)| active)
+            .map(|((x, y), _)| {
+                let mut coord = [0; MAX_DIMS];
+                coord[0] = x as isize;
+                coord[1] = y as isize;
+                packing.pack(&coord)
+            })
+            .collect();
+        return ConwayCubeGrid {
+            dims,
+            packing,
+            active,
+            reach,
+            mirrored: false,
+            rule: "B3/S23".parse().unwrap(),
+            neighborhood: Neighborhood::Moore,
+        };
+    }
+
+    /// Replaces the puzzle's rule (`B3/S23` with a Moore neighborhood). Line
+    /// of sight is the same as Moore here since the pocket has no floor.
+    pub fn with_rule(self, rule: Rule, neighborhood: Neighborhood) -> ConwayCubeGrid {
+        assert!(
+            !rule.is_birth(0),
+            "B0 rules would activate every cube in the infinite pocket"
+        );
+        return ConwayCubeGrid {
+            rule,
+            neighborhood,
+            ..self
+        };
+    }
+
+    /// Switches to storing one cube out of each set of cubes that are the
+    /// same up to negating and reordering the coordinates past the first
+    /// two, which cuts memory and work by up to (N - 2)! * 2^(N - 2). Only
+    /// valid for grids with that symmetry, which is the case for every grid
+    /// produced by `parse` and `step`.
+    pub fn mirrored(self) -> ConwayCubeGrid {
+        let packing = self.packing;
+        let mut coord = [0; MAX_DIMS];
+        let active = self
+            .active
+            .iter()
+            .filter(|&&key| {
+                packing.unpack(key, &mut coord);
+                let higher = &coord[2..self.dims];
+                higher.iter().all(|&c| c >= 0) && higher.windows(2).all(|w| w[0] <= w[1])
+            })
+            .cloned()
+            .collect();
+        return ConwayCubeGrid {
+            active,
+            mirrored: true,
+            ..self
+        };
+    }
+
+    pub fn dims(&self) -> usize {
+        return self.dims;
+    }
+
+    /// The number of cycles that can be run before the cubes could get too
+    /// far from the origin to be stored.
+    pub fn max_cycles(&self) -> usize {
+        return (self.packing.limit() - self.reach) as usize;
+    }
+
+    /// Number of active cubes, including mirror images that aren't stored.
+    pub fn len(&self) -> usize {
+        if !self.mirrored {
+            return self.active.len();
+        }
+        let mut coord = [0; MAX_DIMS];
+        return self
+            .active
+            .iter()
+            .map(|&key| {
+                self.packing.unpack(key, &mut coord);
+                orbit_size(&coord[2..self.dims]) as usize
+            })
+            .sum();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.active.is_empty();
+    }
+
+    /// Returns every active cube, expanding the mirror images of a
+    /// mirrored grid.
+    pub fn active_cells(&self) -> Vec<Coordinate> {
+        let mut cells = Vec::new();
+        let mut coord = [0; MAX_DIMS];
+        for &key in &self.active {
+            self.packing.unpack(key, &mut coord);
+            let coord = &mut coord[..self.dims];
+            if !self.mirrored {
+                cells.push(coord.to_vec());
+                continue;
+            }
+            // Every distinct ordering of the higher coordinates, starting
+            // from the sorted one, with every combination of signs.
+            loop {
+                let nonzero: Vec<usize> = (2..self.dims).filter(|&axis| coord[axis] != 0).collect();
+                for reflections in 0..(1usize << nonzero.len()) {
+                    let mut image = coord.to_vec();
+                    for (i, &axis) in nonzero.iter().enumerate() {
+                        if reflections & (1 << i) != 0 {
+                            image[axis] = -image[axis];
+                        }
+                    }
+                    cells.push(image);
+                }
+                if !next_permutation(&mut coord[2..]) {
+                    break;
+                }
+            }
+        }
+        return cells;
+    }
+
+    pub fn is_active(&self, coord: &[isize]) -> bool {
+        assert_eq!(coord.len(), self.dims, "coordinate has wrong dimensions");
+        if coord.iter().any(|c| c.abs() > self.packing.limit()) {
+            return false;
+        }
+        let mut canonical = [0; MAX_DIMS];
+        canonical[..self.dims].copy_from_slice(coord);
+        if self.mirrored {
+            canonicalize(&mut canonical[2..self.dims]);
+        }
+        return self.active.contains(&self.packing.pack(&canonical));
+    }
+
+    /// Runs one cycle of the grid's rule. With the puzzle's rule, an active
+    /// cube stays active with 2 or 3 active neighbors, and an inactive cube
+    /// becomes active with exactly 3.
+    ///
+    /// Rather than sweeping the bounding box, every active cube adds one to
+    /// the count of each of its neighbors, so only cubes next to an active
+    /// cube are ever visited.
+    pub fn step(&self) -> ConwayCubeGrid {
+        assert!(
+            self.reach < self.packing.limit(),
+            "cubes would get too far from the origin to be stored"
+        );
+        let offsets = neighbor_offsets(self.dims, self.neighborhood);
+        let mut counts = if self.mirrored {
+            self.mirrored_counts(&offsets)
+        } else {
+            let offsets: Vec<Key> = offsets
+                .iter()
+                .map(|offset| self.packing.pack_offset(offset))
+                .collect();
+            let mut counts: FxHashMap<Key, usize> = FxHashMap::default();
+            for &key in &self.active {
+                for &offset in &offsets {
+                    *counts.entry(key.wrapping_add(offset)).or_insert(0) += 1;
+                }
+            }
+            counts
+        };
+
+        // Active cubes with no active neighbors are never reached above, but
+        // they still need to be checked in case the rule has S0.
+        for &key in &self.active {
+            let _ = counts.entry(key).or_insert(0);
+        }
+
+        let active = counts
+            .into_iter()
+            .filter(|(key, count)| self.rule.next_state(self.active.contains(key), *count))
+            .map(|(key, _)| key)
+            .collect();
+        return ConwayCubeGrid {
+            active,
+            reach: self.reach + 1,
+            rule: self.rule.clone(),
+            ..*self
+        };
+    }
+
+    // Counts the active neighbors of each stored cube in a mirrored grid.
+    //
+    // Only the stored cube of each set of images has its neighbors visited.
+    // If A has a images and N has n images, then counting pairs of adjacent
+    // images both ways, a times the number of images of N next to A equals n
+    // times the number of images of A next to N. So each neighbor found
+    // adds a to N's total, which is divided by n at the end.
+    fn mirrored_counts(&self, offsets: &[Coordinate]) -> FxHashMap<Key, usize> {
+        let xy_mask: Key = (1 << (2 * self.packing.bits)) - 1;
+        // Cubes that only differ in x and y have the same neighbors up to
+        // a shift, so the canonical neighbors of each distinct set of higher
+        // coordinates are worked out once, merged, and counted.
+        let mut transitions: FxHashMap<Key, (u64, Vec<(Key, u64)>)> = FxHashMap::default();
+        let mut totals: FxHashMap<Key, u64> = FxHashMap::default();
+        for &key in &self.active {
+            let (images, neighbors) = transitions
+                .entry(key & !xy_mask)
+                .or_insert_with(|| self.transitions(key, offsets));
+            for &(delta, count) in neighbors.iter() {
+                *totals
+                    .entry((key & xy_mask).wrapping_add(delta))
+                    .or_insert(0) += *images * count;
+            }
+        }
+        let mut coord = [0; MAX_DIMS];
+        let mut orbit_sizes: FxHashMap<Key, u64> = FxHashMap::default();
+        return totals
+            .into_iter()
+            .map(|(key, total)| {
+                let images = *orbit_sizes.entry(key & !xy_mask).or_insert_with(|| {
+                    self.packing.unpack(key, &mut coord);
+                    orbit_size(&coord[2..self.dims])
+                });
+                debug_assert_eq!(total % images, 0);
+                (key, (total / images) as usize)
+            })
+            .collect();
+    }
+
+    // Returns the number of images of the cube `key`, and its canonical
+    // neighbors as deltas to add to its x and y alone, each with the number
+    // of neighbors that have it as their canonical form.
+    fn transitions(&self, key: Key, offsets: &[Coordinate]) -> (u64, Vec<(Key, u64)>) {
+        let xy_mask: Key = (1 << (2 * self.packing.bits)) - 1;
+        let mut coord = [0; MAX_DIMS];
+        let mut neighbor = [0; MAX_DIMS];
+        self.packing.unpack(key, &mut coord);
+        let mut deltas: FxHashMap<Key, u64> = FxHashMap::default();
+        for offset in offsets {
+            neighbor[..2].copy_from_slice(&offset[..2]);
+            for axis in 2..self.dims {
+                neighbor[axis] = coord[axis] + offset[axis];
+            }
+            canonicalize(&mut neighbor[2..self.dims]);
+            let delta = self
+                .packing
+                .pack_offset(&offset[..2])
+                .wrapping_add(self.packing.pack(&neighbor) & !xy_mask);
+            *deltas.entry(delta).or_insert(0) += 1;
+        }
+        return (
+            orbit_size(&coord[2..self.dims]),
+            deltas.into_iter().collect(),
+        );
+    }
+}
+
+// Puts the coordinates past the first two in canonical form: non-negative
+// and in ascending order.
+fn canonicalize(higher: &mut [isize]) {
+    for c in higher.iter_mut() {
+        *c = c.abs();
+    }
+    higher.sort_unstable();
+}
+
+// The number of cubes that are the same as one with the canonical higher
+// coordinates `higher` up to negating and reordering them: the number of
+// distinct orderings, times 2 for every nonzero coordinate.
+fn orbit_size(higher: &[isize]) -> u64 {
+    let mut orderings = factorial(higher.len());
+    for run in higher.chunk_by(|a, b| a == b) {
+        orderings /= factorial(run.len());
+    }
+    let nonzero = higher.iter().filter(|&&c| c != 0).count();
+    return orderings << nonzero;
+}
+
+fn factorial(n: usize) -> u64 {
+    return (1..=n as u64).product();
+}
+
+// Rearranges `values` into the next ordering in lexicographic order,
+// returning false if they were already in the last one.
+fn next_permutation(values: &mut [isize]) -> bool {
+    let i = match (1..values.len()).rev().find(|&i| values[i - 1] < values[i]) {
+        Some(i) => i,
+        None => return false,
+    };
+    let j = (i..values.len())
+        .rev()
+        .find(|&j| values[j] > values[i - 1])
+        .unwrap();
+    values.swap(i - 1, j);
+    values[i..].reverse();
+    return true;
+}
+
+// Returns the offsets of the cubes in a cube's neighborhood: the 3^N - 1
+// surrounding cubes for Moore, or the 2N face-adjacent cubes for von Neumann.
+fn neighbor_offsets(dims: usize, neighborhood: Neighborhood) -> Vec<Coordinate> {
+    if neighborhood == Neighborhood::VonNeumann {
+        return (0..dims)
+            .flat_map(|axis| {
+                [-1, 1].iter().map(move |&delta| {
+                    let mut offset = vec![0isize; dims];
+                    offset[axis] = delta;
+                    offset
+                })
+            })
+            .collect();
+    }
+    let mut offsets = Vec::new();
+    let mut offset = vec![-1isize; dims];
+    loop {
+        if offset.iter().any(|&o| o != 0) {
+            offsets.push(offset.clone());
+        }
+        let mut axis = dims;
+        loop {
+            if axis == 0 {
+                return offsets;
+            }
+            axis -= 1;
+            if offset[axis] < 1 {
+                offset[axis] += 1;
+                break;
+            }
+            offset[axis] = -1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = ".#.\n..#\n###\n";
+
+    fn run(grid: ConwayCubeGrid, cycles: usize) -> usize {
+        return (0..cycles).fold(grid, |grid, _| grid.step()).len();
+    }
+
+    #[test]
+    fn example_in_3d() {
+        assert_eq!(run(ConwayCubeGrid::parse(EXAMPLE.as_bytes(), 3), 6), 112);
+    }
+
+    #[test]
+    fn example_in_4d() {
+        assert_eq!(run(ConwayCubeGrid::parse(EXAMPLE.as_bytes(), 4), 6), 848);
+    }
+
+    #[test]
+    fn mirrored_matches_full() {
+        for dims in 3..=6 {
+            let grid = ConwayCubeGrid::parse(EXAMPLE.as_bytes(), dims);
+            assert_eq!(
+                run(grid.clone().mirrored(), 3),
+                run(grid, 3),
+                "{} dims",
+                dims
+            );
+        }
+        let grid = ConwayCubeGrid::parse(EXAMPLE.as_bytes(), 4)
+            .with_rule("B2/S".parse().unwrap(), Neighborhood::VonNeumann);
+        assert_eq!(run(grid.clone().mirrored(), 4), run(grid, 4));
+    }
+
+    #[test]
+    fn mirrored_cells_expand_to_every_image() {
+        let grid = ConwayCubeGrid::parse(EXAMPLE.as_bytes(), 5);
+        let full = (0..2).fold(grid.clone(), |grid, _| grid.step());
+        let mirrored = (0..2).fold(grid.mirrored(), |grid, _| grid.step());
+        let mut expected = full.active_cells();
+        let mut cells = mirrored.active_cells();
+        expected.sort();
+        cells.sort();
+        assert_eq!(cells, expected);
+        assert!(mirrored.is_active(&expected[0]));
+        assert!(!mirrored.is_active(&[0, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn ragged_rows_are_padded() {
+        let grid = ConwayCubeGrid::parse(".#\n..#\n###".as_bytes(), 3);
+        assert_eq!(grid.len(), 5);
+        assert!(grid.is_active(&[2, 1, 0]));
+        assert!(!grid.is_active(&[2, 0, 0]));
+    }
+
+    #[test]
+    fn packing_round_trips() {
+        for dims in 2..=MAX_DIMS {
+            let packing = Packing::new(dims);
+            let limit = packing.limit();
+            let coord: Vec<isize> = (0..dims as isize)
+                .map(|axis| if axis % 2 == 0 { -limit } else { limit - axis })
+                .collect();
+            let mut unpacked = [0; MAX_DIMS];
+            packing.unpack(packing.pack(&coord), &mut unpacked);
+            assert_eq!(&unpacked[..dims], &coord[..]);
+
+            let offset: Vec<isize> = (0..dims).map(|axis| [1, -1, 0][axis % 3]).collect();
+            let moved = packing
+                .pack(&coord)
+                .wrapping_add(packing.pack_offset(&offset));
+            packing.unpack(moved, &mut unpacked);
+            let expected: Vec<isize> = coord.iter().zip(&offset).map(|(c, o)| c + o).collect();
+            assert_eq!(&unpacked[..dims], &expected[..]);
+        }
+    }
+
+    #[test]
+    fn orbit_sizes() {
+        assert_eq!(orbit_size(&[]), 1);
+        assert_eq!(orbit_size(&[0, 0]), 1);
+        assert_eq!(orbit_size(&[0, 1]), 4);
+        assert_eq!(orbit_size(&[1, 1]), 4);
+        assert_eq!(orbit_size(&[0, 1, 2]), 24);
+    }
+}
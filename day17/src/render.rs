@@ -0,0 +1,178 @@
+use crate::ConwayCubeGrid;
+use grid::Grid;
+use std::collections::{BTreeMap, HashSet};
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+/// Renders a grid in the puzzle's format: one x/y slice per combination of
+/// the remaining coordinates, each headed by those coordinates (e.g.
+/// `z=-1, w=0`). All slices share the x/y bounding box of the active cubes,
+/// and slices without any active cubes are omitted.
+pub fn render_slices(grid: &ConwayCubeGrid) -> String {
+    let cells = grid.active_cells();
+    if cells.is_empty() {
+        return "(no active cubes)\n".to_string();
+    }
+    let min_x = cells.iter().map(|c| c[0]).min().unwrap();
+    let max_x = cells.iter().map(|c| c[0]).max().unwrap();
+    let min_y = cells.iter().map(|c| c[1]).min().unwrap();
+    let max_y = cells.iter().map(|c| c[1]).max().unwrap();
+
+    // Keyed by the reversed higher coordinates so that z varies fastest,
+    // matching the order used in the puzzle description.
+    let mut slices: BTreeMap<Vec<isize>, HashSet<(isize, isize)>> = BTreeMap::new();
+    for coord in &cells {
+        let key = coord[2..].iter().rev().cloned().collect();
+        slices.entry(key).or_default().insert((coord[0], coord[1]));
+    }
+
+    let mut output = String::new();
+    for (key, slice) in &slices {
+        if !key.is_empty() {
+            let header: Vec<String> = key
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, value)| format!("{}={}", axis_name(i + 2), value))
+                .collect();
+            output.push_str(&header.join(", "));
+            output.push('\n');
+        }
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                output.push(if slice.contains(&(x, y)) { '#' } else { '.' });
+            }
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+    return output;
+}
+
+fn axis_name(axis: usize) -> String {
+    return match axis {
+        0 => "x".to_string(),
+        1 => "y".to_string(),
+        2 => "z".to_string(),
+        3 => "w".to_string(),
+        n => format!("d{}", n),
+    };
+}
+
+/// Takes the x/y slice at the coordinates `slice` (one per axis past the
+/// first two) out of every generation in `grids`. All frames share one
+/// bounding box so they line up when animated.
+pub fn slice_frames(grids: &[ConwayCubeGrid], slice: &[isize]) -> Vec<Grid<bool>> {
+    let slice_cells: Vec<Vec<(isize, isize)>> = grids
+        .iter()
+        .map(|grid| {
+            assert_eq!(grid.dims() - 2, slice.len(), "slice has wrong dimensions");
+            grid.active_cells()
+                .into_iter()
+                .filter(|coord| &coord[2..] == slice)
+                .map(|coord| (coord[0], coord[1]))
+                .collect()
+        })
+        .collect();
+    let all_cells = || slice_cells.iter().flatten();
+    let min_x = all_cells().map(|c| c.0).min().unwrap_or(0);
+    let max_x = all_cells().map(|c| c.0).max().unwrap_or(0);
+    let min_y = all_cells().map(|c| c.1).min().unwrap_or(0);
+    let max_y = all_cells().map(|c| c.1).max().unwrap_or(0);
+    let width = (max_x - min_x + 1) as usize;
+    let height = (max_y - min_y + 1) as usize;
+
+    return slice_cells
+        .iter()
+        .map(|cells| {
+            let mut frame = Grid::filled(width, height, false);
+            for &(x, y) in cells {
+                frame[((x - min_x) as usize, (y - min_y) as usize)] = true;
+            }
+            frame
+        })
+        .collect();
+}
+
+// The size in pixels of `cells` cells at `scale`, or an error if it's zero
+// or too large for the image format's `T`.
+fn scaled_size<T: TryFrom<usize>>(cells: usize, scale: usize) -> io::Result<T> {
+    if scale == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "scale must be positive",
+        ));
+    }
+    return cells
+        .checked_mul(scale)
+        .and_then(|size| T::try_from(size).ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} cells at scale {} is too large for the image",
+                    cells, scale
+                ),
+            )
+        });
+}
+
+// Expands each cell of `frame` into a `scale` x `scale` block of pixels,
+// using `active` and `inactive` as the pixel values.
+fn scaled_pixels(frame: &Grid<bool>, scale: usize, active: u8, inactive: u8) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(frame.width() * frame.height() * scale * scale);
+    for y in 0..frame.height() * scale {
+        for x in 0..frame.width() * scale {
+            pixels.push(match frame[(x / scale, y / scale)] {
+                true => active,
+                false => inactive,
+            });
+        }
+    }
+    return pixels;
+}
+
+/// Writes `frame` as a grayscale PNG with white active cubes.
+pub fn write_png(path: &Path, frame: &Grid<bool>, scale: usize) -> io::Result<()> {
+    let width: u32 = scaled_size(frame.width(), scale)?;
+    let height: u32 = scaled_size(frame.height(), scale)?;
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&scaled_pixels(frame, scale, 0xff, 0x20))?;
+    return Ok(());
+}
+
+/// Writes `frames` as a looping animated GIF, showing each frame for
+/// `delay_ms` milliseconds.
+pub fn write_gif(
+    path: &Path,
+    frames: &[Grid<bool>],
+    scale: usize,
+    delay_ms: u16,
+) -> io::Result<()> {
+    let (width, height): (u16, u16) = match frames.first() {
+        Some(frame) => (
+            scaled_size(frame.width(), scale)?,
+            scaled_size(frame.height(), scale)?,
+        ),
+        None => return Ok(()),
+    };
+    let palette = [0x20, 0x20, 0x20, 0xff, 0xff, 0xff];
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = gif::Encoder::new(file, width, height, &palette).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    for frame in frames {
+        let mut gif_frame =
+            gif::Frame::from_indexed_pixels(width, height, scaled_pixels(frame, scale, 1, 0), None);
+        gif_frame.delay = delay_ms / 10;
+        encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+    }
+    return Ok(());
+}
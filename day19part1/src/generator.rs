@@ -0,0 +1,367 @@
+use crate::Rule;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+// Returns up to `limit` messages accepted by rule `start`, sorted and
+// deduplicated, or None if the grammar reachable from `start` is recursive
+// (in which case the language may be infinite and `sample` should be used
+// instead). Every intermediate expansion is cut off at `limit` as well, so
+// finite grammars with huge languages are never expanded in full; ambiguous
+// grammars may yield fewer than `limit` distinct messages.
+pub fn enumerate(rules: &HashMap<u32, Rule>, start: u32, limit: usize) -> Option<Vec<String>> {
+    let mut memo = HashMap::new();
+    let mut visiting = HashSet::new();
+    let mut messages = expand_rule_number(start, rules, limit, &mut memo, &mut visiting)?;
+    messages.truncate(limit);
+    messages.sort();
+    messages.dedup();
+    return Some(messages);
+}
+
+fn expand_rule_number(
+    rule_number: u32,
+    rules: &HashMap<u32, Rule>,
+    limit: usize,
+    memo: &mut HashMap<u32, Vec<String>>,
+    visiting: &mut HashSet<u32>,
+) -> Option<Vec<String>> {
+    if let Some(messages) = memo.get(&rule_number) {
+        return Some(messages.clone());
+    }
+    if !visiting.insert(rule_number) {
+        return None;
+    }
+    let messages = expand(
+        rules.get(&rule_number).unwrap(),
+        rules,
+        limit,
+        memo,
+        visiting,
+    )?;
+    visiting.remove(&rule_number);
+    memo.insert(rule_number, messages.clone());
+    return Some(messages);
+}
+
+fn expand(
+    rule: &Rule,
+    rules: &HashMap<u32, Rule>,
+    limit: usize,
+    memo: &mut HashMap<u32, Vec<String>>,
+    visiting: &mut HashSet<u32>,
+) -> Option<Vec<String>> {
+    return match rule {
+        Rule::Character(c) => Some(vec![c.to_string()]),
+        Rule::Sequence(seq) => {
+            let mut messages = vec![String::new()];
+            for &rule_number in seq {
+                let suffixes = expand_rule_number(rule_number, rules, limit, memo, visiting)?;
+                messages = messages
+                    .iter()
+                    .flat_map(|prefix| suffixes.iter().map(move |suffix| prefix.clone() + suffix))
+                    .take(limit)
+                    .collect();
+            }
+            Some(messages)
+        }
+        Rule::Or(r1, r2) => {
+            let mut messages = expand(r1, rules, limit, memo, visiting)?;
+            messages.extend(expand(r2, rules, limit, memo, visiting)?);
+            messages.truncate(limit);
+            Some(messages)
+        }
+    };
+}
+
+// Computes the length of the shortest message each rule can produce. Rules
+// that can never terminate (e.g. `8: 8`) are absent from the result.
+fn min_lengths(rules: &HashMap<u32, Rule>) -> HashMap<u32, usize> {
+    let mut lengths = HashMap::new();
+    loop {
+        let mut changed = false;
+        for (&rule_number, rule) in rules {
+            if let Some(length) = min_length(rule, &lengths) {
+                if lengths.get(&rule_number).is_none_or(|&l| length < l) {
+                    lengths.insert(rule_number, length);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return lengths;
+        }
+    }
+}
+
+fn min_length(rule: &Rule, lengths: &HashMap<u32, usize>) -> Option<usize> {
+    return match rule {
+        Rule::Character(_) => Some(1),
+        Rule::Sequence(seq) => seq.iter().map(|n| lengths.get(n)).sum(),
+        Rule::Or(r1, r2) => match (min_length(r1, lengths), min_length(r2, lengths)) {
+            (Some(l1), Some(l2)) => Some(l1.min(l2)),
+            (l1, l2) => l1.or(l2),
+        },
+    };
+}
+
+// Samples `count` random messages accepted by rule `start` that are at most
+// `max_len` characters long. Works for recursive grammars, since alternatives
+// are only chosen if their shortest expansion still fits in the remaining
+// length budget. Returns an empty vector if no message fits.
+pub fn sample<R: Rng>(
+    rules: &HashMap<u32, Rule>,
+    start: u32,
+    max_len: usize,
+    count: usize,
+    rng: &mut R,
+) -> Vec<String> {
+    let lengths = min_lengths(rules);
+    match lengths.get(&start) {
+        Some(&length) if length <= max_len => {}
+        _ => return Vec::new(),
+    }
+    let rule = rules.get(&start).unwrap();
+    return (0..count)
+        .map(|_| {
+            let mut msg = String::new();
+            sample_rule(rule, rules, &lengths, max_len, rng, &mut msg);
+            msg
+        })
+        .collect();
+}
+
+fn sample_rule<R: Rng>(
+    rule: &Rule,
+    rules: &HashMap<u32, Rule>,
+    lengths: &HashMap<u32, usize>,
+    budget: usize,
+    rng: &mut R,
+    msg: &mut String,
+) -> usize {
+    return match rule {
+        Rule::Character(c) => {
+            msg.push(*c);
+            1
+        }
+        Rule::Sequence(seq) => {
+            let mut remaining = budget;
+            for (i, rule_number) in seq.iter().enumerate() {
+                let reserved: usize = seq[i + 1..].iter().map(|n| lengths[n]).sum();
+                remaining -= sample_rule(
+                    rules.get(rule_number).unwrap(),
+                    rules,
+                    lengths,
+                    remaining - reserved,
+                    rng,
+                    msg,
+                );
+            }
+            budget - remaining
+        }
+        Rule::Or(r1, r2) => {
+            let fits = |r: &Rule| min_length(r, lengths).is_some_and(|l| l <= budget);
+            let rule = match (fits(r1), fits(r2)) {
+                (true, true) => {
+                    if rng.gen() {
+                        r1
+                    } else {
+                        r2
+                    }
+                }
+                (true, false) => r1,
+                (false, true) => r2,
+                (false, false) => unreachable!("no alternative fits in {} characters", budget),
+            };
+            sample_rule(rule, rules, lengths, budget, rng, msg)
+        }
+    };
+}
+
+// Returns every character that appears in a terminal rule.
+pub fn alphabet(rules: &HashMap<u32, Rule>) -> Vec<char> {
+    let mut chars: Vec<char> = rules
+        .values()
+        .filter_map(|rule| match rule {
+            Rule::Character(c) => Some(*c),
+            _ => None,
+        })
+        .collect();
+    chars.sort_unstable();
+    chars.dedup();
+    return chars;
+}
+
+// Applies a single random edit (substitution, insertion, deletion, or swap
+// of adjacent characters) to produce a near-miss of `msg`. The result is not
+// guaranteed to be rejected by the grammar; check it with `accepts`.
+pub fn mutate<R: Rng>(msg: &str, alphabet: &[char], rng: &mut R) -> String {
+    let mut chars: Vec<char> = msg.chars().collect();
+    // No edit applies to an empty message without characters to insert.
+    if chars.is_empty() && alphabet.is_empty() {
+        return msg.to_string();
+    }
+    loop {
+        match rng.gen_range(0..4) {
+            0 if !chars.is_empty() && alphabet.len() > 1 => {
+                let i = rng.gen_range(0..chars.len());
+                let c = loop {
+                    let c = alphabet[rng.gen_range(0..alphabet.len())];
+                    if c != chars[i] {
+                        break c;
+                    }
+                };
+                chars[i] = c;
+            }
+            1 if !alphabet.is_empty() => {
+                let i = rng.gen_range(0..=chars.len());
+                chars.insert(i, alphabet[rng.gen_range(0..alphabet.len())]);
+            }
+            2 if !chars.is_empty() => {
+                let _ = chars.remove(rng.gen_range(0..chars.len()));
+            }
+            3 if chars.len() >= 2 => {
+                let i = rng.gen_range(0..chars.len() - 1);
+                chars.swap(i, i + 1);
+            }
+            _ => continue,
+        }
+        return chars.into_iter().collect();
+    }
+}
+
+// Reference matcher used to label generated messages. Unlike `eval_rule`,
+// this backtracks through every alternative, so it handles ambiguous and
+// (right-)recursive grammars correctly at the cost of speed.
+pub fn accepts(rules: &HashMap<u32, Rule>, start: u32, msg: &[char]) -> bool {
+    return match_ends(rules.get(&start).unwrap(), rules, msg, 0).contains(&msg.len());
+}
+
+fn match_ends(rule: &Rule, rules: &HashMap<u32, Rule>, msg: &[char], pos: usize) -> Vec<usize> {
+    return match rule {
+        Rule::Character(c) => {
+            if msg.get(pos) == Some(c) {
+                vec![pos + 1]
+            } else {
+                Vec::new()
+            }
+        }
+        Rule::Sequence(seq) => {
+            let mut ends = vec![pos];
+            for rule_number in seq {
+                let rule = rules.get(rule_number).unwrap();
+                let mut next_ends: Vec<usize> = ends
+                    .iter()
+                    .flat_map(|&end| match_ends(rule, rules, msg, end))
+                    .collect();
+                next_ends.sort_unstable();
+                next_ends.dedup();
+                ends = next_ends;
+                if ends.is_empty() {
+                    break;
+                }
+            }
+            ends
+        }
+        Rule::Or(r1, r2) => {
+            let mut ends = match_ends(r1, rules, msg, pos);
+            ends.extend(match_ends(r2, rules, msg, pos));
+            ends
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rule;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const EXAMPLE: &str = "0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: \"a\"
+5: \"b\"";
+
+    // Rule 0 matches one or more "ab"s followed by a single "b".
+    const RECURSIVE: &str = "0: 1 2
+1: 3 | 3 1
+2: \"b\"
+3: 4 2
+4: \"a\"";
+
+    fn rules(s: &str) -> HashMap<u32, Rule> {
+        return s.lines().map(|line| parse_rule(line.to_string())).collect();
+    }
+
+    fn chars(s: &str) -> Vec<char> {
+        return s.chars().collect();
+    }
+
+    #[test]
+    fn enumerate_finite_grammar() {
+        let rules = rules(EXAMPLE);
+        let expected = vec![
+            "aaaabb", "aaabab", "abbabb", "abbbab", "aabaab", "aabbbb", "abaaab", "ababbb",
+        ];
+        let mut expected: Vec<String> = expected.into_iter().map(String::from).collect();
+        expected.sort();
+        assert_eq!(enumerate(&rules, 0, usize::MAX), Some(expected.clone()));
+        assert_eq!(enumerate(&rules, 0, 100), Some(expected));
+        assert_eq!(
+            enumerate(&rules, 2, usize::MAX),
+            Some(vec!["aa".to_string(), "bb".to_string()])
+        );
+    }
+
+    #[test]
+    fn enumerate_stops_at_limit() {
+        let rules = rules(EXAMPLE);
+        let messages = enumerate(&rules, 0, 3).unwrap();
+        assert_eq!(messages.len(), 3);
+        assert!(messages.iter().all(|msg| accepts(&rules, 0, &chars(msg))));
+        assert_eq!(enumerate(&rules, 0, 0), Some(Vec::new()));
+    }
+
+    #[test]
+    fn enumerate_recursive_grammar() {
+        let rules = rules(RECURSIVE);
+        assert_eq!(enumerate(&rules, 0, usize::MAX), None);
+        assert_eq!(
+            enumerate(&rules, 3, usize::MAX),
+            Some(vec!["ab".to_string()])
+        );
+    }
+
+    #[test]
+    fn samples_are_accepted() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for (grammar, max_len) in [(EXAMPLE, 6), (RECURSIVE, 3), (RECURSIVE, 20)] {
+            let rules = rules(grammar);
+            let messages = sample(&rules, 0, max_len, 100, &mut rng);
+            assert_eq!(messages.len(), 100);
+            for msg in messages {
+                assert!(msg.len() <= max_len, "{} is longer than {}", msg, max_len);
+                assert!(accepts(&rules, 0, &chars(&msg)), "{} was rejected", msg);
+            }
+        }
+    }
+
+    #[test]
+    fn samples_too_short() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(sample(&rules(EXAMPLE), 0, 5, 10, &mut rng).is_empty());
+        assert!(sample(&rules(RECURSIVE), 0, 2, 10, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn accepts_recursive_grammar() {
+        let rules = rules(RECURSIVE);
+        assert!(accepts(&rules, 0, &chars("abb")));
+        assert!(accepts(&rules, 0, &chars("abababb")));
+        assert!(!accepts(&rules, 0, &chars("ab")));
+        assert!(!accepts(&rules, 0, &chars("abab")));
+        assert!(!accepts(&rules, 0, &chars("babb")));
+    }
+}
@@ -1,13 +1,38 @@
+#![allow(
+    clippy::needless_return,
+    clippy::needless_borrow,
+    clippy::trim_split_whitespace
+)]
+
 use std::collections::HashMap;
 use std::env;
 use std::fmt;
 use std::fs::File;
 use std::io::{self, BufRead};
+use std::process;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+mod generator;
+
+const USAGE: &str =
+    "usage: day19part1 <path to input text file> [generate|check] [count] [max length] [seed]";
 
 fn main() {
+    if let Err(err) = run() {
+        eprintln!("{}", err);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
     let args: Vec<String> = env::args().collect();
-    let filename = &args[1];
-    let file = File::open(filename).unwrap();
+    let filename = args.get(1).ok_or("missing input file")?;
+    let file =
+        File::open(filename).map_err(|err| format!("couldn't open {}: {}", filename, err))?;
 
     let mut parse_stage = ParseStage::Rules;
     let mut rules = HashMap::new();
@@ -26,17 +51,95 @@ fn main() {
             },
         });
 
-    println!(
-        "{}",
-        messages
+    // Optional trailing arguments: <mode> [count] [max length] [seed]
+    let count = parse_arg(&args, 3, "count", 10)?;
+    let max_len = parse_arg(&args, 4, "max length", 32)?;
+    let mut rng = StdRng::seed_from_u64(parse_arg(&args, 5, "seed", 0)?);
+    match args.get(2).map(|s| s.as_str()) {
+        None => println!(
+            "{}",
+            messages
+                .iter()
+                .filter(|msg| message_matches(&rules, msg))
+                .count()
+        ),
+        Some("generate") => generate_messages(&rules, count, max_len, &mut rng)
             .iter()
-            .map(|msg| {
-                let (matches, count) =
-                    eval_rule(rules.get(&0).unwrap(), &rules, &str_to_chars(msg.clone()));
-                matches && (count == msg.len())
-            })
-            .filter(|&x| x)
-            .count()
+            .for_each(|msg| println!("{}", msg)),
+        Some("check") => check_messages(&rules, count, max_len, &mut rng),
+        Some(mode) => return Err(format!("unknown mode: {}", mode)),
+    }
+    return Ok(());
+}
+
+// Parses the optional numeric argument at `index`, if it was passed.
+fn parse_arg<T: FromStr>(
+    args: &[String],
+    index: usize,
+    name: &str,
+    default: T,
+) -> Result<T, String> {
+    return match args.get(index) {
+        Some(s) => s
+            .parse()
+            .map_err(|_| format!("{} needs a number, not {}", name, s)),
+        None => Ok(default),
+    };
+}
+
+fn message_matches(rules: &HashMap<u32, Rule>, msg: &str) -> bool {
+    let (matches, count) = eval_rule(
+        rules.get(&0).unwrap(),
+        rules,
+        &str_to_chars(msg.to_string()),
+    );
+    return matches && (count == msg.len());
+}
+
+// Enumerates up to `count` messages accepted by rule 0 if the grammar is
+// finite, otherwise samples `count` messages of up to `max_len` characters.
+fn generate_messages(
+    rules: &HashMap<u32, Rule>,
+    count: usize,
+    max_len: usize,
+    rng: &mut StdRng,
+) -> Vec<String> {
+    return generator::enumerate(rules, 0, count)
+        .unwrap_or_else(|| generator::sample(rules, 0, max_len, count, rng));
+}
+
+// Differential test of `eval_rule` against the backtracking reference
+// matcher: every generated message must be accepted, and a mutated
+// near-miss of each one that the grammar rejects must be rejected.
+fn check_messages(rules: &HashMap<u32, Rule>, count: usize, max_len: usize, rng: &mut StdRng) {
+    let alphabet = generator::alphabet(rules);
+    let positives = generate_messages(rules, count, max_len, rng);
+    let negatives: Vec<String> = positives
+        .iter()
+        .map(|msg| generator::mutate(msg, &alphabet, rng))
+        .filter(|msg| !generator::accepts(rules, 0, &str_to_chars(msg.clone())))
+        .collect();
+
+    let false_negatives: Vec<&String> = positives
+        .iter()
+        .filter(|msg| !message_matches(rules, msg))
+        .collect();
+    let false_positives: Vec<&String> = negatives
+        .iter()
+        .filter(|msg| message_matches(rules, msg))
+        .collect();
+    false_negatives
+        .iter()
+        .for_each(|msg| println!("rejected valid message: {}", msg));
+    false_positives
+        .iter()
+        .for_each(|msg| println!("accepted invalid message: {}", msg));
+    println!(
+        "{}/{} valid messages accepted, {}/{} invalid messages rejected",
+        positives.len() - false_negatives.len(),
+        positives.len(),
+        negatives.len() - false_positives.len(),
+        negatives.len()
     );
 }
 
@@ -0,0 +1,124 @@
+use crate::search::{self, Ranking};
+use crate::{count_trees, parse_map, parse_slopes, Slope};
+use std::env;
+use std::fs::File;
+use std::io;
+use std::process;
+
+const USAGE: &str = "usage: <path to input text file> [--slope <right,down | right/down>]...
+    [--slopes <path to slope list>]
+    [--search [--right <range>] [--down <range>] [--top <n>]]";
+
+// The number of slopes listed in each table in --search mode.
+const DEFAULT_TOP: usize = 10;
+
+/// Counts the trees hit sledding down the map in the input file along
+/// `default_slopes`, or the slopes given on the command line. With more
+/// than one slope, a table of the trees hit on each is printed before their
+/// product. In --search mode, every slope in the given ranges is tried
+/// instead, by default right 1..=width and down 1..=height, and the best and
+/// worst are ranked. Errors are printed with the usage text, and the process
+/// exits with status 1.
+pub fn run(default_slopes: &[Slope]) {
+    if let Err(err) = run_args(default_slopes) {
+        eprintln!("{}", err);
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+}
+
+fn run_args(default_slopes: &[Slope]) -> Result<(), String> {
+    let mut args = Vec::new();
+    let mut slopes = Vec::new();
+    let mut search = false;
+    let mut rights = None;
+    let mut downs = None;
+    let mut top = DEFAULT_TOP;
+    let mut arg_iter = env::args();
+    while let Some(arg) = arg_iter.next() {
+        let mut value = || {
+            arg_iter
+                .next()
+                .ok_or_else(|| format!("{} needs a value", arg))
+        };
+        match arg.as_str() {
+            "--slope" => slopes.push(value()?.parse()?),
+            "--slopes" => {
+                let path = value()?;
+                let file =
+                    File::open(&path).map_err(|err| format!("couldn't open {}: {}", path, err))?;
+                slopes.extend(parse_slopes(io::BufReader::new(file))?);
+            }
+            "--search" => search = true,
+            "--right" => rights = Some(search::parse_range(&value()?)?),
+            "--down" => downs = Some(search::parse_range(&value()?)?),
+            "--top" => {
+                let s = value()?;
+                top = s
+                    .parse()
+                    .map_err(|_| format!("--top needs a number, not {}", s))?
+            }
+            _ => args.push(arg),
+        }
+    }
+    if slopes.is_empty() {
+        slopes = default_slopes.to_vec();
+    }
+    let filename = args.get(1).ok_or("missing input file")?;
+    let file =
+        File::open(filename).map_err(|err| format!("couldn't open {}: {}", filename, err))?;
+    let map = parse_map(io::BufReader::new(file)).map_err(|err| err.to_string())?;
+
+    if search {
+        let rights = rights.unwrap_or(1..=map.width() as isize);
+        let downs = downs.unwrap_or(1..=map.height() as isize);
+        if *downs.start() <= 0 {
+            return Err("slopes must go down".to_string());
+        }
+        let Ranking { fewest, most } = search::search(&map, rights, downs, top);
+        println!("fewest trees");
+        print!("{}", ranked_table(&fewest));
+        println!();
+        println!("most trees");
+        print!("{}", ranked_table(&most));
+        return Ok(());
+    }
+
+    let counts: Vec<usize> = slopes
+        .iter()
+        .map(|&slope| count_trees(&map, slope))
+        .collect();
+    if slopes.len() > 1 {
+        print!("{}", table(&slopes, &counts));
+    }
+    println!("{}", counts.iter().product::<usize>());
+    return Ok(());
+}
+
+// Lines up each slope with the number of trees hit on it.
+fn table(slopes: &[Slope], counts: &[usize]) -> String {
+    let labels: Vec<String> = slopes.iter().map(|slope| slope.to_string()).collect();
+    let width = labels.iter().map(|l| l.len()).max().unwrap_or(0).max(5) + 3;
+    let mut table = format!("{:<width$}trees\n", "slope", width = width);
+    for (label, count) in labels.iter().zip(counts) {
+        table.push_str(&format!("{:<width$}{:>5}\n", label, count, width = width));
+    }
+    return table;
+}
+
+// Lists slopes in order with their rank and the number of trees hit on them.
+fn ranked_table(slopes: &[(Slope, usize)]) -> String {
+    let labels: Vec<String> = slopes.iter().map(|(slope, _)| slope.to_string()).collect();
+    let width = labels.iter().map(|l| l.len()).max().unwrap_or(0).max(5) + 3;
+    let mut table = format!("rank  {:<width$}trees\n", "slope", width = width);
+    for (rank, (label, (_, count))) in labels.iter().zip(slopes).enumerate() {
+        table.push_str(&format!(
+            "{:<6}{:<width$}{:>5}\n",
+            rank + 1,
+            label,
+            count,
+            width = width
+        ));
+    }
+    return table;
+}
@@ -0,0 +1,165 @@
+#![allow(clippy::needless_return)]
+
+use grid::{Edges, Grid, ParseError};
+use std::fmt;
+use std::io::BufRead;
+use std::str::FromStr;
+
+pub mod cli;
+pub mod search;
+
+/// Parses the map, with `#` for a tree and `.` for open ground.
+pub fn parse_map<R: BufRead>(reader: R) -> Result<Grid<bool>, ParseError<InputError>> {
+    return Grid::parse(reader, |c| match c {
+        '.' => Ok(false),
+        '#' => Ok(true),
+        _ => Err(InputError::InvalidCharacter(c)),
+    });
+}
+
+/// How far the toboggan moves between the positions where trees are
+/// counted. `right` is negative for slopes that go left.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Slope {
+    pub right: isize,
+    pub down: isize,
+}
+
+impl FromStr for Slope {
+    type Err = String;
+
+    /// Parses either a step, `right,down` (e.g. `3,1` or `-1,2`), or a
+    /// rational number of columns per row, `right/down` (e.g. `1/3` for one
+    /// column right every three rows down). A step is taken as written; a
+    /// rational slope passes through every position on the line x = y *
+    /// right / down with integral coordinates, so `2/6` is the same as `1/3`.
+    fn from_str(s: &str) -> Result<Slope, String> {
+        let s = s.trim();
+        let (separator, rational) = match (s.find(','), s.find('/')) {
+            (Some(i), None) => (i, false),
+            (None, Some(i)) => (i, true),
+            _ => return Err(format!("invalid slope: {}", s)),
+        };
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<isize>()
+                .map_err(|_| format!("invalid slope: {}", s))
+        };
+        let right = parse(&s[..separator])?;
+        let down = parse(&s[separator + 1..])?;
+        if down <= 0 {
+            return Err(format!("slope must go down: {}", s));
+        }
+        if !rational {
+            return Ok(Slope { right, down });
+        }
+        let divisor = gcd(right.unsigned_abs(), down as usize) as isize;
+        return Ok(Slope {
+            right: right / divisor,
+            down: down / divisor,
+        });
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.right, self.down)
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    return if b == 0 { a } else { gcd(b, a % b) };
+}
+
+/// Parses one slope per line, skipping blank lines.
+pub fn parse_slopes<R: BufRead>(reader: R) -> Result<Vec<Slope>, String> {
+    return reader
+        .lines()
+        .map(|line| line.unwrap())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse())
+        .collect();
+}
+
+/// Counts the trees hit going from the top left corner to the bottom of
+/// `map` at `slope`. The map repeats to the left and right.
+pub fn count_trees(map: &Grid<bool>, slope: Slope) -> usize {
+    return map
+        .ray((0, 0), (slope.right, slope.down), Edges::WrapX)
+        .filter(|&position| map[position])
+        .count();
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InputError::InvalidCharacter(c) => write!(f, "invalid character: {}", c),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub const EXAMPLE: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    fn slope(s: &str) -> Slope {
+        return s.parse().unwrap();
+    }
+
+    #[test]
+    fn part_1_example() {
+        let map = parse_map(EXAMPLE.as_bytes()).unwrap();
+        assert_eq!(count_trees(&map, slope("3,1")), 7);
+    }
+
+    #[test]
+    fn part_2_example() {
+        let map = parse_map(EXAMPLE.as_bytes()).unwrap();
+        let slopes = parse_slopes("1,1\n3,1\n5,1\n7,1\n\n1,2\n".as_bytes()).unwrap();
+        let counts: Vec<usize> = slopes.iter().map(|&s| count_trees(&map, s)).collect();
+        assert_eq!(counts, vec![2, 7, 3, 4, 2]);
+        assert_eq!(counts.iter().product::<usize>(), 336);
+    }
+
+    #[test]
+    fn parse_slope() {
+        assert_eq!(slope(" -1,2 "), Slope { right: -1, down: 2 });
+        assert_eq!(slope("2,6"), Slope { right: 2, down: 6 });
+        assert_eq!(slope("2/6"), Slope { right: 1, down: 3 });
+        assert_eq!(slope("0/4"), Slope { right: 0, down: 1 });
+        for s in &["3", "3,0", "1/-2", "1,2/3", "a,1"] {
+            assert!(s.parse::<Slope>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn ragged_map() {
+        assert!(matches!(
+            parse_map("..#\n.#\n".as_bytes()),
+            Err(ParseError::RaggedRow {
+                row: 1,
+                width: 2,
+                expected: 3
+            })
+        ));
+    }
+}
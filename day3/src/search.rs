@@ -0,0 +1,173 @@
+// Finds the slopes that hit the fewest and the most trees out of every
+// step (right, down) in a range.
+//
+// Counting each slope separately reads a scattered cell from every row it
+// passes through. Instead, for each `down`, the rows it visits are read once
+// in order and every `right` is advanced through each of them together. On a
+// map that repeats horizontally, `right` only matters modulo the width, so
+// each distinct remainder is only counted once however wide the range is.
+
+use crate::Slope;
+use grid::Grid;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::RangeInclusive;
+
+/// The slopes with the fewest and the most trees, in order from the fewest
+/// and from the most respectively. Ties are broken by `down` and then by
+/// `right`, smallest first.
+pub struct Ranking {
+    pub fewest: Vec<(Slope, usize)>,
+    pub most: Vec<(Slope, usize)>,
+}
+
+/// Counts the trees on every slope with `right` in `rights` and `down` in
+/// `downs`, keeping the `count` best and worst. Every `down` must be
+/// positive.
+pub fn search(
+    map: &Grid<bool>,
+    rights: RangeInclusive<isize>,
+    downs: RangeInclusive<isize>,
+    count: usize,
+) -> Ranking {
+    assert!(*downs.start() > 0, "slopes must go down");
+    let width = map.width() as isize;
+    // Each remainder of `right` that occurs in the range.
+    let residues: Vec<usize> = if width == 0 {
+        Vec::new()
+    } else if rights.end() - rights.start() + 1 >= width {
+        (0..width as usize).collect()
+    } else {
+        rights
+            .clone()
+            .map(|right| right.rem_euclid(width) as usize)
+            .collect()
+    };
+
+    // Each heap holds the `count` smallest keys seen so far, with the
+    // largest of them on top so that it's the one replaced. Keys are
+    // (trees, down, right), with trees reversed for the most trees.
+    let mut fewest = BinaryHeap::new();
+    let mut most = BinaryHeap::new();
+    let mut trees_by_residue = vec![0; map.width()];
+    for down in downs {
+        count_residues(map, down as usize, &residues, &mut trees_by_residue);
+        for right in rights.clone() {
+            let trees = if width == 0 {
+                0
+            } else {
+                trees_by_residue[right.rem_euclid(width) as usize]
+            };
+            push_bounded(&mut fewest, (trees, down, right), count);
+            push_bounded(&mut most, (Reverse(trees), down, right), count);
+        }
+    }
+
+    let slope = |right, down| Slope { right, down };
+    return Ranking {
+        fewest: fewest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(trees, down, right)| (slope(right, down), trees))
+            .collect(),
+        most: most
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(Reverse(trees), down, right)| (slope(right, down), trees))
+            .collect(),
+    };
+}
+
+// Sets trees[r] to the number of trees hit at the slope (r, down) for every
+// r in `residues`, walking the rows that `down` visits in order.
+fn count_residues(map: &Grid<bool>, down: usize, residues: &[usize], trees: &mut [usize]) {
+    let width = map.width();
+    let mut columns = vec![0; residues.len()];
+    let mut counts = vec![0; residues.len()];
+    for y in (down..map.height()).step_by(down) {
+        let row = map.row(y);
+        for ((column, count), &residue) in columns.iter_mut().zip(&mut counts).zip(residues) {
+            *column += residue;
+            if *column >= width {
+                *column -= width;
+            }
+            *count += row[*column] as usize;
+        }
+    }
+    for (&residue, &count) in residues.iter().zip(&counts) {
+        trees[residue] = count;
+    }
+}
+
+/// Parses an inclusive range written as `start..=end`, `start..end` or a
+/// single number.
+pub fn parse_range(s: &str) -> Result<RangeInclusive<isize>, String> {
+    let parse = |n: &str| {
+        n.trim()
+            .parse::<isize>()
+            .map_err(|_| format!("invalid range: {}", s))
+    };
+    if let Some(i) = s.find("..=") {
+        return Ok(parse(&s[..i])?..=parse(&s[i + 3..])?);
+    }
+    if let Some(i) = s.find("..") {
+        return Ok(parse(&s[..i])?..=parse(&s[i + 2..])? - 1);
+    }
+    let n = parse(s)?;
+    return Ok(n..=n);
+}
+
+fn push_bounded<T: Ord>(heap: &mut BinaryHeap<T>, item: T, count: usize) {
+    if heap.len() < count {
+        heap.push(item);
+    } else if let Some(mut top) = heap.peek_mut() {
+        if item < *top {
+            *top = item;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::EXAMPLE;
+    use crate::{count_trees, parse_map};
+
+    #[test]
+    fn search_agrees_with_count_trees() {
+        let map = parse_map(EXAMPLE.as_bytes()).unwrap();
+        let Ranking { fewest, most } = search(&map, -15..=15, 1..=12, 1000);
+        assert_eq!(fewest.len(), 31 * 12);
+        for (slope, trees) in &fewest {
+            assert_eq!(count_trees(&map, *slope), *trees, "{}", slope);
+        }
+        // The same slopes in the opposite order, with ties still broken by
+        // the smallest step first.
+        let key = |&(slope, trees): &(Slope, usize)| (trees, slope.down, slope.right);
+        assert!(fewest.windows(2).all(|w| key(&w[0]) < key(&w[1])));
+        let reversed = |&(slope, trees): &(Slope, usize)| (Reverse(trees), slope.down, slope.right);
+        assert!(most.windows(2).all(|w| reversed(&w[0]) < reversed(&w[1])));
+    }
+
+    #[test]
+    fn search_keeps_the_top_slopes() {
+        let map = parse_map(EXAMPLE.as_bytes()).unwrap();
+        let Ranking { fewest, most } = search(&map, 1..=7, 1..=2, 2);
+        assert_eq!(
+            fewest,
+            vec![
+                (Slope { right: 5, down: 2 }, 0),
+                (Slope { right: 2, down: 1 }, 1)
+            ]
+        );
+        assert_eq!(most[0], (Slope { right: 3, down: 1 }, 7));
+    }
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!(parse_range("1..=5"), Ok(1..=5));
+        assert_eq!(parse_range("-3..3"), Ok(-3..=2));
+        assert_eq!(parse_range("4"), Ok(4..=4));
+        assert!(parse_range("1..x").is_err());
+    }
+}
@@ -0,0 +1,445 @@
+#![allow(clippy::needless_return)]
+
+use std::fmt;
+use std::io::{self, BufRead};
+use std::ops::{Index, IndexMut};
+
+/// The four directions that differ by 1 along a single axis, as (dx, dy)
+/// with y increasing downwards.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// The eight directions that differ by at most 1 along each axis.
+pub const ADJACENT: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// What happens to positions that fall off the edge of a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edges {
+    /// Positions outside the grid don't exist.
+    Bounded,
+    /// The grid repeats horizontally, but not vertically.
+    WrapX,
+    /// The grid repeats in both directions.
+    Wrap,
+}
+
+/// A rectangular grid of cells, stored row-major. Positions are (x, y) with
+/// (0, 0) in the top left corner.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells in row-major order.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "wrong number of cells");
+        return Grid {
+            width,
+            height,
+            cells,
+        };
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        return Grid::from_cells(width, height, vec![value; width * height]);
+    }
+
+    /// Parses one row per line, using `f` to turn each character into a
+    /// cell. Blank lines are skipped, and every row must be as wide as the
+    /// first.
+    pub fn parse<R, E, F>(reader: R, f: F) -> Result<Grid<T>, ParseError<E>>
+    where
+        R: BufRead,
+        F: FnMut(char) -> Result<T, E>,
+    {
+        let rows = parse_rows(reader, f)?;
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some((row, cells)) = rows.iter().enumerate().find(|(_, r)| r.len() != width) {
+            return Err(ParseError::RaggedRow {
+                row,
+                width: cells.len(),
+                expected: width,
+            });
+        }
+        return Ok(Grid::from_cells(
+            width,
+            rows.len(),
+            rows.into_iter().flatten().collect(),
+        ));
+    }
+
+    /// Like `parse`, but rows may have different widths, and the short ones
+    /// are padded with `fill` to the width of the widest.
+    pub fn parse_padded<R, E, F>(reader: R, fill: T, f: F) -> Result<Grid<T>, ParseError<E>>
+    where
+        R: BufRead,
+        F: FnMut(char) -> Result<T, E>,
+        T: Clone,
+    {
+        let rows = parse_rows(reader, f)?;
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for row in &rows {
+            cells.extend_from_slice(row);
+            cells.resize(cells.len() + width - row.len(), fill.clone());
+        }
+        return Ok(Grid::from_cells(width, rows.len(), cells));
+    }
+
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    /// The cells in row-major order.
+    pub fn cells(&self) -> &[T] {
+        return &self.cells;
+    }
+
+    /// The index of (x, y) in `cells`.
+    pub fn index_of(&self, x: usize, y: usize) -> usize {
+        return y * self.width + x;
+    }
+
+    /// The position of the cell at `index` in `cells`.
+    pub fn position(&self, index: usize) -> (usize, usize) {
+        return (index % self.width, index / self.width);
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        return Some(&self.cells[self.index_of(x, y)]);
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = self.index_of(x, y);
+        return Some(&mut self.cells[index]);
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        return &self.cells[y * self.width..(y + 1) * self.width];
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks() panics on a zero width, and a grid without columns has
+        // no cells to show anyway.
+        return self.cells.chunks(self.width.max(1));
+    }
+
+    /// Iterates over every cell with its position, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        return self
+            .cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell));
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        return Grid::from_cells(self.width, self.height, self.cells.iter().map(f).collect());
+    }
+
+    /// Brings an arbitrary position onto the grid according to `edges`,
+    /// returning `None` if it is off a bounded edge.
+    pub fn resolve(&self, x: isize, y: isize, edges: Edges) -> Option<(usize, usize)> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+        let (width, height) = (self.width as isize, self.height as isize);
+        let x = match edges {
+            Edges::Bounded => x,
+            Edges::WrapX | Edges::Wrap => x.rem_euclid(width),
+        };
+        let y = match edges {
+            Edges::Bounded | Edges::WrapX => y,
+            Edges::Wrap => y.rem_euclid(height),
+        };
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return None;
+        }
+        return Some((x as usize, y as usize));
+    }
+
+    /// The position `offset` away from (x, y).
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+        edges: Edges,
+    ) -> Option<(usize, usize)> {
+        return self.resolve(x as isize + dx, y as isize + dy, edges);
+    }
+
+    /// The positions one step away from `position` in each of `directions`
+    /// (e.g. `ADJACENT`), skipping those off a bounded edge. On a wrapping
+    /// grid narrower than 3 cells the same position can appear more than
+    /// once.
+    pub fn neighbors<'a>(
+        &'a self,
+        position: (usize, usize),
+        directions: &'a [(isize, isize)],
+        edges: Edges,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        return directions
+            .iter()
+            .filter_map(move |&direction| self.offset(position, direction, edges));
+    }
+
+    /// The positions visited by repeatedly stepping `direction` from
+    /// `position`, not including `position` itself. The ray ends at a
+    /// bounded edge, or when wrapping brings it back to where it started.
+    pub fn ray(
+        &self,
+        position: (usize, usize),
+        direction: (isize, isize),
+        edges: Edges,
+    ) -> Ray<'_, T> {
+        assert!(direction != (0, 0), "a ray needs a direction");
+        return Ray {
+            grid: self,
+            start: position,
+            current: Some(position),
+            direction,
+            edges,
+        };
+    }
+
+    /// Renders the grid with one character per cell.
+    pub fn render<F: Fn(&T) -> char>(&self, f: F) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            output.extend(row.iter().map(&f));
+            output.push('\n');
+        }
+        return output;
+    }
+}
+
+// Parses the non-blank lines of `reader` into rows of cells.
+fn parse_rows<T, R, E, F>(reader: R, mut f: F) -> Result<Vec<Vec<T>>, ParseError<E>>
+where
+    R: BufRead,
+    F: FnMut(char) -> Result<T, E>,
+{
+    let mut rows = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(ParseError::Io)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let row = line
+            .chars()
+            .map(&mut f)
+            .collect::<Result<Vec<T>, E>>()
+            .map_err(ParseError::InvalidCell)?;
+        rows.push(row);
+    }
+    return Ok(rows);
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is off the grid",
+            x,
+            y
+        );
+        return &self.cells[self.index_of(x, y)];
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is off the grid",
+            x,
+            y
+        );
+        let index = self.index_of(x, y);
+        return &mut self.cells[index];
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the positions along a line through a grid. See
+/// `Grid::ray`.
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    start: (usize, usize),
+    current: Option<(usize, usize)>,
+    direction: (isize, isize),
+    edges: Edges,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let next = self
+            .grid
+            .offset(self.current?, self.direction, self.edges)
+            .filter(|&position| position != self.start);
+        self.current = next;
+        return next;
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseError<E> {
+    Io(io::Error),
+    InvalidCell(E),
+    RaggedRow {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "{}", e),
+            ParseError::InvalidCell(e) => write!(f, "{}", e),
+            ParseError::RaggedRow {
+                row,
+                width,
+                expected,
+            } => write!(
+                f,
+                "row {} is {} cells wide, expected {}",
+                row, width, expected
+            ),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ParseError<E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+
+    fn parse(s: &str) -> Result<Grid<char>, ParseError<Infallible>> {
+        return Grid::parse(s.as_bytes(), Ok);
+    }
+
+    #[test]
+    fn parse_skips_blank_lines_and_trailing_whitespace() {
+        let grid = parse("ab\r\n\ncd  \n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.cells(), &['a', 'b', 'c', 'd']);
+        assert_eq!(grid.to_string(), "ab\ncd\n");
+        assert_eq!(parse("").unwrap().height(), 0);
+    }
+
+    #[test]
+    fn parse_rejects_ragged_rows() {
+        assert!(matches!(
+            parse("abc\nab\n"),
+            Err(ParseError::RaggedRow {
+                row: 1,
+                width: 2,
+                expected: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_padded_fills_short_rows() {
+        let grid =
+            Grid::parse_padded("a\nabc\nab".as_bytes(), '.', Ok::<char, Infallible>).unwrap();
+        assert_eq!(grid.render(|&c| c), "a..\nabc\nab.\n");
+    }
+
+    #[test]
+    fn parse_reports_invalid_cells() {
+        let result = Grid::parse("..#\n.x.".as_bytes(), |c| match c {
+            '.' | '#' => Ok(c == '#'),
+            _ => Err(c),
+        });
+        assert!(matches!(result, Err(ParseError::InvalidCell('x'))));
+    }
+
+    #[test]
+    fn positions() {
+        let mut grid = Grid::filled(3, 2, 0);
+        grid[(2, 1)] = 5;
+        assert_eq!(grid.index_of(2, 1), 5);
+        assert_eq!(grid.position(5), (2, 1));
+        assert_eq!(grid.get(2, 1), Some(&5));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.row(1), &[0, 0, 5]);
+    }
+
+    #[test]
+    fn edges() {
+        let grid = Grid::filled(3, 2, ());
+        assert_eq!(grid.resolve(-1, 0, Edges::Bounded), None);
+        assert_eq!(grid.resolve(-1, 0, Edges::WrapX), Some((2, 0)));
+        assert_eq!(grid.resolve(0, 2, Edges::WrapX), None);
+        assert_eq!(grid.resolve(4, -1, Edges::Wrap), Some((1, 1)));
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::filled(3, 3, ());
+        assert_eq!(grid.neighbors((1, 1), &ADJACENT, Edges::Bounded).count(), 8);
+        let corner: Vec<(usize, usize)> = grid
+            .neighbors((0, 0), &ORTHOGONAL, Edges::Bounded)
+            .collect();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors((0, 0), &ADJACENT, Edges::Wrap).count(), 8);
+    }
+
+    #[test]
+    fn rays() {
+        let grid = Grid::filled(4, 5, ());
+        let bounded: Vec<(usize, usize)> = grid.ray((0, 0), (1, 1), Edges::Bounded).collect();
+        assert_eq!(bounded, vec![(1, 1), (2, 2), (3, 3)]);
+        let wrapped: Vec<(usize, usize)> = grid.ray((0, 0), (3, 1), Edges::WrapX).collect();
+        assert_eq!(wrapped, vec![(3, 1), (2, 2), (1, 3), (0, 4)]);
+        // A wrapping ray stops when it gets back to where it started.
+        assert_eq!(grid.ray((1, 1), (2, 0), Edges::Wrap).count(), 1);
+        assert_eq!(grid.ray((1, 1), (0, -1), Edges::Wrap).count(), 4);
+    }
+}
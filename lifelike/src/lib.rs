@@ -0,0 +1,260 @@
+#![allow(clippy::needless_return)]
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// A life-like cellular automaton rule in B/S notation, e.g. `B3/S23` for
+/// Conway's Game of Life: a dead cell is born with exactly 3 live neighbors,
+/// and a live cell survives with 2 or 3.
+///
+/// Neighbor counts are written as single digits (`B36/S23`), or as a comma
+/// separated list of counts and inclusive ranges for neighborhoods with more
+/// than 9 cells (`B3/S2,3,10-12`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    birth: BTreeSet<usize>,
+    survival: BTreeSet<usize>,
+}
+
+impl Rule {
+    pub fn is_birth(&self, neighbors: usize) -> bool {
+        return self.birth.contains(&neighbors);
+    }
+
+    pub fn is_survival(&self, neighbors: usize) -> bool {
+        return self.survival.contains(&neighbors);
+    }
+
+    /// Checks that every neighbor count in the rule is possible with
+    /// `neighborhood` in `dims` dimensions.
+    pub fn check_counts(&self, neighborhood: Neighborhood, dims: usize) -> Result<(), RuleError> {
+        let neighbors = neighborhood.size(dims);
+        return match self.birth.iter().chain(&self.survival).max() {
+            Some(&count) if count > neighbors => Err(RuleError::CountTooLarge(count, neighbors)),
+            _ => Ok(()),
+        };
+    }
+
+    /// Returns whether a cell is alive in the next generation.
+    pub fn next_state(&self, alive: bool, neighbors: usize) -> bool {
+        return match alive {
+            true => self.is_survival(neighbors),
+            false => self.is_birth(neighbors),
+        };
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Rule, RuleError> {
+        let mut birth = None;
+        let mut survival = None;
+        for part in s.split('/') {
+            let mut chars = part.chars();
+            let counts = match chars.next() {
+                Some('B') | Some('b') if birth.is_none() => &mut birth,
+                Some('S') | Some('s') if survival.is_none() => &mut survival,
+                _ => return Err(RuleError::InvalidRule(s.to_string())),
+            };
+            *counts = Some(
+                parse_counts(chars.as_str())
+                    .ok_or_else(|| RuleError::InvalidRule(s.to_string()))?,
+            );
+        }
+        return match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Rule { birth, survival }),
+            _ => Err(RuleError::InvalidRule(s.to_string())),
+        };
+    }
+}
+
+// The largest neighbor count a rule can mention: the size of the Moore
+// neighborhood in 8 dimensions, well past what's practical to simulate.
+// Rules are checked against the actual neighborhood with `check_counts`.
+const MAX_COUNT: usize = 6560;
+
+fn parse_counts(s: &str) -> Option<BTreeSet<usize>> {
+    if !s.contains(',') && !s.contains('-') {
+        return s
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as usize))
+            .collect();
+    }
+    let mut counts = BTreeSet::new();
+    for item in s.split(',') {
+        let mut bounds = item.splitn(2, '-');
+        let start = bounds.next()?.trim().parse::<usize>().ok()?;
+        let end = match bounds.next() {
+            Some(end) => end.trim().parse::<usize>().ok()?,
+            None => start,
+        };
+        if start > end || end > MAX_COUNT {
+            return None;
+        }
+        counts.extend(start..=end);
+    }
+    return Some(counts);
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "B{}/S{}",
+            format_counts(&self.birth),
+            format_counts(&self.survival)
+        )
+    }
+}
+
+fn format_counts(counts: &BTreeSet<usize>) -> String {
+    if counts.iter().all(|&n| n < 10) {
+        return counts.iter().map(|n| n.to_string()).collect();
+    }
+    return counts
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+}
+
+/// Which cells count as neighbors of a cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Neighborhood {
+    /// Every cell that differs by at most 1 along each axis.
+    Moore,
+    /// Only the cells that differ by 1 along a single axis.
+    VonNeumann,
+    /// The first non-floor cell in each of the Moore directions. On a grid
+    /// without floor this is the same as `Moore`.
+    LineOfSight,
+}
+
+impl Neighborhood {
+    /// The number of neighbors of a cell in `dims` dimensions.
+    pub fn size(&self, dims: usize) -> usize {
+        return match self {
+            Neighborhood::Moore | Neighborhood::LineOfSight => {
+                3usize.saturating_pow(dims as u32) - 1
+            }
+            Neighborhood::VonNeumann => 2 * dims,
+        };
+    }
+}
+
+impl FromStr for Neighborhood {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Neighborhood, RuleError> {
+        return match s.to_lowercase().as_str() {
+            "moore" => Ok(Neighborhood::Moore),
+            "vonneumann" | "von-neumann" => Ok(Neighborhood::VonNeumann),
+            "lineofsight" | "line-of-sight" => Ok(Neighborhood::LineOfSight),
+            _ => Err(RuleError::InvalidNeighborhood(s.to_string())),
+        };
+    }
+}
+
+impl fmt::Display for Neighborhood {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Neighborhood::Moore => write!(f, "moore"),
+            Neighborhood::VonNeumann => write!(f, "von-neumann"),
+            Neighborhood::LineOfSight => write!(f, "line-of-sight"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RuleError {
+    InvalidRule(String),
+    InvalidNeighborhood(String),
+    // A neighbor count and the size of the neighborhood it's too large for.
+    CountTooLarge(usize, usize),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::InvalidRule(s) => write!(f, "invalid rule: {}", s),
+            RuleError::InvalidNeighborhood(s) => write!(f, "invalid neighborhood: {}", s),
+            RuleError::CountTooLarge(count, neighbors) => write!(
+                f,
+                "the rule needs {} neighbors, but cells only have {}",
+                count, neighbors
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(s: &str) -> Rule {
+        return s.parse().unwrap();
+    }
+
+    #[test]
+    fn parse_digits() {
+        let life = rule("B3/S23");
+        assert!(life.is_birth(3) && !life.is_birth(2));
+        assert!(life.is_survival(2) && life.is_survival(3) && !life.is_survival(4));
+        assert!(life.next_state(false, 3) && !life.next_state(true, 1));
+        assert_eq!(rule("s23/b3"), life);
+        assert_eq!(rule("B/S").to_string(), "B/S");
+    }
+
+    #[test]
+    fn parse_lists_and_ranges() {
+        let r = rule("B3,10-12/S2,3");
+        assert!(r.is_birth(3) && r.is_birth(10) && r.is_birth(12) && !r.is_birth(13));
+        assert_eq!(r.to_string(), "B3,10,11,12/S23");
+        assert_eq!(rule(&r.to_string()), r);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        for s in &[
+            "",
+            "B3",
+            "B3/S23/S1",
+            "B3/B2",
+            "X3/S23",
+            "B3a/S23",
+            "B5-3/S2",
+            "B1-7000/S2",
+        ] {
+            assert!(s.parse::<Rule>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn neighborhood_sizes() {
+        assert_eq!(Neighborhood::Moore.size(2), 8);
+        assert_eq!(Neighborhood::LineOfSight.size(3), 26);
+        assert_eq!(Neighborhood::VonNeumann.size(4), 8);
+        assert_eq!(
+            "line-of-sight".parse::<Neighborhood>().unwrap(),
+            Neighborhood::LineOfSight
+        );
+        assert!("hex".parse::<Neighborhood>().is_err());
+    }
+
+    #[test]
+    fn check_counts() {
+        assert!(rule("B3/S23")
+            .check_counts(Neighborhood::VonNeumann, 2)
+            .is_ok());
+        assert!(matches!(
+            rule("B5/S23").check_counts(Neighborhood::VonNeumann, 2),
+            Err(RuleError::CountTooLarge(5, 4))
+        ));
+        assert!(rule("B9/S").check_counts(Neighborhood::Moore, 2).is_err());
+        assert!(rule("B9/S").check_counts(Neighborhood::Moore, 3).is_ok());
+    }
+}